@@ -9,15 +9,21 @@ mod player_action_listener;
 use app::App;
 use log::info;
 
-use std::sync::mpsc;
+use std::{
+    collections::BTreeMap,
+    sync::{mpsc, Arc, Mutex},
+};
 
 use color_eyre::Result;
+use dyn_clone::DynClone;
 
 use crate::{
     engine::{
         player::PlayerID,
-        prelude::CardID,
+        prelude::{CardID, PermanentID},
+        combat::AttackTarget,
         ability::{AbilityID, AssignedAbility}, card_play::AssignedCardPlay,
+        target::Target,
     },
     client::{
         player_action_listener::PlayerActionListener,
@@ -25,11 +31,36 @@ use crate::{
     }
 };
 
-use tokio::sync::broadcast::Receiver as BroadcastReceiver;
+use tokio::sync::broadcast::{channel, Receiver as BroadcastReceiver, Sender as BroadcastSender};
+
+pub use crate::engine::game_state_snapshot::GameStateSnapshot;
+
+/// How the game asks players for decisions, and shows them what they can
+/// see. Cloning a game clones its interface too, so interfaces connected
+/// to real players share the connection between the clones.
+pub trait PlayerInterface: DynClone + Send {
+    fn choose_options(&mut self, player: PlayerID, options: PlayerActionRequest) -> PlayerActionResponse;
+    fn update_state(&mut self, player: PlayerID, snapshot: GameStateSnapshot);
+}
+
+dyn_clone::clone_trait_object!(PlayerInterface);
+
+/// A TUI client for each player.
+#[derive(Clone)]
+pub struct Clients {
+    clients: Arc<Mutex<BTreeMap<PlayerID, Client>>>,
+    state_update_senders: BTreeMap<PlayerID, BroadcastSender<GameStateSnapshot>>,
+}
 
-/// Stand in for an actual type that the server can send us
+/// Always picks the first option, which passes or declines. Useful for
+/// simulations and tests.
 #[derive(Clone)]
-pub struct GameStateSnapshot;
+pub struct FirstOption;
+
+/// The interface of a game nobody is connected to yet. Being asked for a
+/// decision is a bug, so it panics rather than deciding for the player.
+#[derive(Clone)]
+pub struct Disconnected;
 
 pub struct Client {
     player_id: PlayerID,
@@ -49,6 +80,72 @@ pub enum PlayerAction {
     Pass,
     CardPlay(AssignedCardPlay, String),
     ActivateAbility(AssignedAbility, String),
+    Target(Target, String),
+    Mode(usize, String),
+    Card(CardID, String),
+    /// A value for X
+    ChooseX(u32),
+    Replacement(AssignedAbility, String),
+    /// Shuffle the opening hand away and draw a new one
+    Mulligan,
+    /// Go back to the last time a player had priority, if everyone agrees
+    Undo,
+    /// The creature attacks the player or planeswalker
+    Attack(PermanentID, AttackTarget, String),
+    /// The first creature blocks the second
+    Block(PermanentID, PermanentID, String),
+}
+
+impl Clients {
+    pub fn launch(players: &[PlayerID]) -> Result<Self> {
+        let cap = 1;
+        let mut clients = BTreeMap::new();
+        let mut state_update_senders = BTreeMap::new();
+        for player in players {
+            let (state_update_sender, state_update_receiver) = channel(cap);
+            clients.insert(*player, Client::launch(*player, state_update_receiver)?);
+            state_update_senders.insert(*player, state_update_sender);
+        }
+
+        Ok(Self {
+            clients: Arc::new(Mutex::new(clients)),
+            state_update_senders,
+        })
+    }
+}
+
+impl PlayerInterface for Clients {
+    fn choose_options(&mut self, player: PlayerID, options: PlayerActionRequest) -> PlayerActionResponse {
+        self.clients
+            .lock()
+            .expect("client lock was poisoned")
+            .get_mut(&player)
+            .expect("Every player has a client")
+            .choose_options(options)
+    }
+
+    fn update_state(&mut self, player: PlayerID, snapshot: GameStateSnapshot) {
+        // Nobody might be listening yet, which is fine.
+        if let Some(sender) = self.state_update_senders.get(&player) {
+            let _ = sender.send(snapshot);
+        }
+    }
+}
+
+impl PlayerInterface for FirstOption {
+    fn choose_options(&mut self, _player: PlayerID, options: PlayerActionRequest) -> PlayerActionResponse {
+        options.into_iter().next().unwrap_or(PlayerAction::Pass)
+    }
+
+    fn update_state(&mut self, _player: PlayerID, _snapshot: GameStateSnapshot) {}
+}
+
+impl PlayerInterface for Disconnected {
+    fn choose_options(&mut self, player: PlayerID, options: PlayerActionRequest) -> PlayerActionResponse {
+        panic!("{:?} was asked to choose from {:?}, but no interface is set", player, options)
+    }
+
+    fn update_state(&mut self, _player: PlayerID, _snapshot: GameStateSnapshot) {}
 }
 
 impl Client {
@@ -1,11 +1,13 @@
+use dyn_clone::DynClone;
 use log::warn;
+use serde::{Serialize, Deserialize};
 
 use crate::engine::prelude::*;
 
 use super::util::id::ID;
 
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Copy, Debug, Hash)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Copy, Debug, Hash, Serialize, Deserialize)]
 pub struct AssignedAbility {
     pub perm: PermanentID,
     pub ability: AbilityID
@@ -17,16 +19,37 @@ impl AssignedAbility {
     }
 }
 
+#[derive(Clone)]
 pub enum AbilityClass {
     Static(Box<dyn QueryModifier>),
     Triggered(Box<dyn EventModifier>),
     Replacement(Box<dyn EventModifier>),
     Activated(AbilityCost, Box<dyn OneShot>),
+    /// A saga chapter; triggers when its permanent's lore counters reach the
+    /// chapter number.
+    Chapter(u32, Box<dyn OneShot>),
+    /// A triggered ability that goes on the stack, and resolves the effect.
+    StackTriggered(Box<dyn Trigger>, Box<dyn OneShot>),
 }
 impl AbilityClass {
     pub fn is_activated(&self) -> bool {
         matches!(self, Self::Activated(..))
     }
+
+    pub fn chapter(&self) -> Option<u32> {
+        match self {
+            Self::Chapter(chapter, _) => Some(*chapter),
+            _ => None,
+        }
+    }
+
+    /// What the ability does when it resolves, if it uses the stack.
+    pub fn one_shot(&self) -> Option<&dyn OneShot> {
+        match self {
+            Self::Activated(_, effect) | Self::Chapter(_, effect) | Self::StackTriggered(_, effect) => Some(effect.as_ref()),
+            _ => None,
+        }
+    }
 }
 
 pub type AbilityID = ID<Ability>;
@@ -38,6 +61,7 @@ pub enum ListenResult {
     Ignored(GameEvent),
 }
 
+#[derive(Clone)]
 pub struct Ability {
     pub id: AbilityID,
     pub base: LatentAbility,
@@ -70,31 +94,83 @@ impl Ability {
     }
 }
 
+#[derive(Clone)]
 pub struct LatentAbility {
     pub class: AbilityClass,
     pub description: String,
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum AbilitySpeed {
     Instant,
     Sorcery,
 }
 
-pub trait Effect{
+/// Effects are shared between clones of a game, which can be sent to
+/// other threads.
+pub trait Effect: Send + Sync {
     /// Called before the game is started. This is the one chance an effect has to modify itself.
     /// most effects don't need this, so a default implementation is provided
     fn setup(&mut self) {}
 }
 
-pub trait QueryModifier: Effect {
+pub trait QueryModifier: Effect + DynClone {
     fn query(&self, ability: AbilityID, perm: PermanentID, query: &mut GameQuery, game: &Game);
 }
 
-pub trait EventModifier: Effect {
+dyn_clone::clone_trait_object!(QueryModifier);
+
+pub trait EventModifier: Effect + DynClone {
     fn listen(&self, _ability: AbilityID, perm: PermanentID, event: GameEvent, _game: &Game) -> ListenResult;
 }
 
-pub trait OneShot: Effect {
-    fn activate(&self, ability: AbilityID, perm: PermanentID, game: &mut Game);
+dyn_clone::clone_trait_object!(EventModifier);
+
+/// Decides when a triggered ability that uses the stack triggers.
+pub trait Trigger: Effect + DynClone {
+    fn triggers_on(&self, ability: AbilityID, perm: PermanentID, event: &GameEvent, game: &Game) -> bool;
+
+    /// An "intervening if" clause. It is checked both when the ability
+    /// triggers and when it resolves; if it is false either time, the 
+    /// ability does nothing. Most triggers don't have one, so a default
+    /// implementation is provided
+    fn condition(&self, _ability: AbilityID, _perm: PermanentID, _game: &Game) -> bool {
+        true
+    }
 }
+
+dyn_clone::clone_trait_object!(Trigger);
+
+pub trait OneShot: Effect + DynClone {
+    fn activate(&self, ability: AbilityID, perm: PermanentID, targets: &[Target], game: &mut Game);
+
+    /// The legal choices for each of this effect's targets. Most effects
+    /// don't target, so a default implementation is provided
+    fn targets(&self, _ability: AbilityID, _perm: PermanentID, _game: &Game) -> TargetRequirements {
+        vec![]
+    }
+
+    /// The modes to choose from while activating, if the effect is modal.
+    /// Most effects aren't, so a default implementation is provided
+    fn modes(&self, _ability: AbilityID, _perm: PermanentID, _game: &Game) -> Option<ModeRequirements> {
+        None
+    }
+
+    /// The legal choices for each of the targets of the given mode.
+    fn mode_targets(&self, _mode: usize, _ability: AbilityID, _perm: PermanentID, _game: &Game) -> TargetRequirements {
+        vec![]
+    }
+
+    /// Called once for each chosen mode, in printed order. `activate`
+    /// isn't called for modal effects.
+    fn activate_mode(&self, _mode: usize, _ability: AbilityID, _perm: PermanentID, _targets: &[Target], _game: &mut Game) {}
+
+    /// The trigger and effect of a delayed trigger this created about the
+    /// subject. Most effects don't create any, so a default implementation
+    /// is provided
+    fn delayed_trigger(&self, _subject: PermanentID) -> Option<(Box<dyn Trigger>, Box<dyn OneShot>)> {
+        None
+    }
+}
+
+dyn_clone::clone_trait_object!(OneShot);
@@ -11,6 +11,7 @@ pub struct AbilityOrdering {
     static_order: Vec<AssignedAbility>,
     replacement_order: Vec<AssignedAbility>,
     trigger_order: Vec<AssignedAbility>,
+    stack_trigger_order: Vec<AssignedAbility>,
 
     // Is only for logging purposes.
     fresh: Cell<bool>,
@@ -22,6 +23,7 @@ impl AbilityOrdering {
             static_order: vec![],
             replacement_order: vec![],
             trigger_order: vec![],
+            stack_trigger_order: vec![],
             fresh: Cell::new(true),
         }
     }
@@ -29,7 +31,7 @@ impl AbilityOrdering {
     pub fn build_from(game: &Game) -> Self {
         let mut order = Self::new();
         let mut seen_abilities = HashSet::new();
-        let mut done = true;
+        let mut done = false;
 
         while !done {
             done = true;
@@ -45,6 +47,7 @@ impl AbilityOrdering {
                 match ability.base.class {
                     AbilityClass::Replacement(_)  => order.replacement_order.push(*as_ability),
                     AbilityClass::Triggered(_)  => order.trigger_order.push(*as_ability),
+                    AbilityClass::StackTriggered(..)  => order.stack_trigger_order.push(*as_ability),
                     AbilityClass::Static(_) => {
                         order.static_order.push(*as_ability);
                         done = false;
@@ -69,17 +72,56 @@ impl AbilityOrdering {
         }
     }
 
+    /// Finds every replacement effect that would modify the event, along 
+    /// with what it would be replaced by. Replacement effects that were 
+    /// already applied to the event are skipped.
+    pub fn replacements(&self, event: &GameEvent, applied: &[AssignedAbility], game: &Game) -> Vec<(AssignedAbility, Vec<GameEvent>)> {
+        self.check_fresh();
+
+        self.replacement_order
+            .iter()
+            .filter(|as_ability| !applied.contains(as_ability))
+            .filter_map(|as_ability| {
+                let ability = game.get(as_ability.ability);
+                match ability.listen(as_ability.perm, event.clone(), game) {
+                    ListenResult::Replaced(new_events) => Some((*as_ability, new_events)),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Finds the triggered abilities that the event causes to be put on 
+    /// the stack. Their intervening if clauses must be true.
+    pub fn stack_triggers(&self, event: &GameEvent, game: &Game) -> Vec<AssignedAbility> {
+        self.check_fresh();
+
+        self.stack_trigger_order
+            .iter()
+            .filter(|as_ability| match game.get(as_ability.ability).base.class {
+                AbilityClass::StackTriggered(ref trigger, _) => 
+                    trigger.triggers_on(as_ability.ability, as_ability.perm, event, game)
+                    && trigger.condition(as_ability.ability, as_ability.perm, game),
+                _ => false,
+            })
+            .cloned()
+            .collect()
+    }
+
     /// Puts event through the ability ordering to apply all trigger
-    /// and replacement effects
+    /// effects. Replacement effects are handled by `replacements`.
     pub fn listen(&self, mut event: GameEvent, game: &Game) -> ListenResult {
         self.check_fresh();
 
         let mut new_events = Vec::new();
-        for as_ability in self.replacement_order.iter().chain(self.trigger_order.iter()) {
+        for as_ability in self.trigger_order.iter() {
             let ability = game.get(as_ability.ability);
             let result = ability.listen(as_ability.perm, event, game); 
             match result {
-                ListenResult::Replaced(_) => return result,
+                ListenResult::Replaced(_) => {
+                    warn!("Triggered ability {:?} replaced an event, it should be a replacement effect", as_ability);
+                    return result
+                }
                 ListenResult::Triggered(ev, triggered) => {
                     event = ev;
                     new_events.extend(triggered);
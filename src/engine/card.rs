@@ -1,59 +1,213 @@
+use std::sync::Arc;
+
+use serde::{Serialize, Deserialize};
+
 use crate::engine::prelude::*;
 
 use super::util::id::ID;
 
 pub type CardID = ID<Card>;
 
+/// How the faces of a card relate to each other.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum CardLayout {
+    Normal,
+    /// Double-faced cards that transform between their faces.
+    Transform,
+    /// Double-faced cards that are cast or played as either face.
+    ModalDoubleFaced,
+    /// Either half can be cast.
+    Split,
+    /// The adventure is cast first, then the card can be cast from exile.
+    Adventure,
+    /// Kamigawa flip cards; the permanent turns upside down.
+    Flip,
+}
+
+/// One face of a card, with its own characteristics and abilities.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Face {
+    pub attrs: Attributes,
+    pub perm_abilities: Vec<AbilityID>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Card {
     pub owner: PlayerID,
     pub id: CardID,
-    pub perm_abilities: Vec<AbilityID>,
+    /// The name of the card definition it was made from. Saved games use
+    /// it to make the card's abilities and card plays again.
+    pub definition: String,
     pub card_plays: Vec<CardPlayID>,
-    pub attrs: Attributes,
+    /// Every face of the card, the front face first. Faces never change,
+    /// so copies of the card share them.
+    pub faces: Arc<Vec<Face>>,
+    pub layout: CardLayout,
+    /// Whether the card is in exile because its adventure resolved. Only
+    /// then can the rest of the card be cast from exile.
+    pub on_adventure: bool,
 }
 
 impl Card {
-    pub fn new(attrs: Attributes, id: CardID, perm_abilities: Vec<AbilityID>, card_plays: Vec<CardPlayID>, owner: PlayerID) -> Self {
+    pub fn new(definition: String, faces: Vec<Face>, layout: CardLayout, id: CardID, card_plays: Vec<CardPlayID>, owner: PlayerID) -> Self {
+        assert!(!faces.is_empty(), "Cards need at least one face");
         Self {
-            attrs,
+            definition,
+            faces: Arc::new(faces),
+            layout,
             id, 
-            perm_abilities, 
-            card_plays, // todo insert actual card plays!!
-            owner
+            card_plays,
+            owner,
+            on_adventure: false,
         }
     }
+
+    /// The characteristics of the front face, which are the card's 
+    /// characteristics outside of the battlefield and stack.
+    pub fn attrs(&self) -> &Attributes {
+        &self.faces[0].attrs
+    }
+
+    pub fn face(&self, face: usize) -> &Face {
+        &self.faces[face]
+    }
 }
 
 pub struct LatentCard {
+    /// The name this card is looked up by, set by whoever defines it.
+    pub definition: String,
     pub attributes: Attributes,
     pub perm_abilities: Vec<LatentAbility>,
     pub card_plays: Vec<CardPlay>,
+    /// Faces other than the front face.
+    pub other_faces: Vec<LatentFace>,
+    pub layout: CardLayout,
 }
 
+pub struct LatentFace {
+    pub attributes: Attributes,
+    pub perm_abilities: Vec<LatentAbility>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Attributes {
     pub name: String,
     pub type_line: TypeLine,
     pub cost: Option<ManaCost>,
     pub flavor: String,
     pub power_toughness: Option<PowerToughness>,
+    /// Starting loyalty, for planeswalkers.
+    pub loyalty: Option<u32>,
+    /// What an aura can enchant.
+    pub enchant: Option<CardType>,
+    pub keywords: Vec<Keyword>,
+    /// "This spell can't be countered."
+    pub cant_be_countered: bool,
+    /// "Affinity for artifacts": costs {1} less for each one you control.
+    pub affinity: Option<CardType>,
+    pub enters_tapped: bool,
 }
 
-impl LatentCard {
-    pub fn new(name: String, cost: ManaCost,  flavor: String, type_line: TypeLine, perm_abilities: Vec<LatentAbility>, card_plays: Vec<CardPlay>, power_toughness: Option<(i32, i32)>) -> Self {
-        let power_toughness = power_toughness.map(|(p, t)|PowerToughness::new(p, t));
-        let cost = Some(cost);
-        let attributes = Attributes { 
+impl Attributes {
+    pub fn new(name: String, cost: Option<ManaCost>, flavor: String, type_line: TypeLine, power_toughness: Option<(i32, i32)>) -> Self {
+        Self {
             name,
             cost,
             flavor,
             type_line,
-            power_toughness,
-        };
+            power_toughness: power_toughness.map(|(p, t)| PowerToughness::new(p, t)),
+            loyalty: None,
+            enchant: None,
+            keywords: vec![],
+            cant_be_countered: false,
+            affinity: None,
+            enters_tapped: false,
+        }
+    }
+}
+
+impl Attributes {
+    /// The colors of the mana symbols in its cost.
+    pub fn colors(&self) -> Vec<ManaType> {
+        let mut colors = vec![];
+        for mana in self.cost.iter().flat_map(|cost| cost.mana.iter()) {
+            if *mana != ManaType::Colorless && !colors.contains(mana) {
+                colors.push(*mana);
+            }
+        }
+        colors
+    }
+}
+
+impl LatentCard {
+    pub fn new(name: String, cost: ManaCost,  flavor: String, type_line: TypeLine, perm_abilities: Vec<LatentAbility>, card_plays: Vec<CardPlay>, power_toughness: Option<(i32, i32)>) -> Self {
+        let attributes = Attributes::new(name, Some(cost), flavor, type_line, power_toughness);
         Self {
+            definition: String::new(),
             attributes,
             perm_abilities,
             card_plays,
+            other_faces: vec![],
+            layout: CardLayout::Normal,
         }
     }
+
+    /// Adds another face to the card. Every face but the front one has
+    /// to be added in order.
+    pub fn with_face(mut self, layout: CardLayout, face: LatentFace) -> Self {
+        self.layout = layout;
+        self.other_faces.push(face);
+        self
+    }
+
+    pub fn enters_tapped(mut self) -> Self {
+        self.attributes.enters_tapped = true;
+        self
+    }
+
+    pub fn with_loyalty(mut self, loyalty: u32) -> Self {
+        self.attributes.loyalty = Some(loyalty);
+        self
+    }
+
+    pub fn with_keyword(mut self, keyword: Keyword) -> Self {
+        self.attributes.keywords.push(keyword);
+        self
+    }
+
+    pub fn cant_be_countered(mut self) -> Self {
+        self.attributes.cant_be_countered = true;
+        self
+    }
+
+    pub fn with_affinity(mut self, card_type: CardType) -> Self {
+        self.attributes.affinity = Some(card_type);
+        self
+    }
+
+    pub fn with_enchant(mut self, card_type: CardType) -> Self {
+        self.attributes.enchant = Some(card_type);
+        self
+    }
 }
 
+
+impl LatentFace {
+    /// Faces that aren't cast, like the back of a transforming card, have no cost.
+    pub fn new(name: String, cost: Option<ManaCost>, type_line: TypeLine, perm_abilities: Vec<LatentAbility>, power_toughness: Option<(i32, i32)>) -> Self {
+        Self {
+            attributes: Attributes::new(name, cost, "".into(), type_line, power_toughness),
+            perm_abilities,
+        }
+    }
+
+    pub fn with_keyword(mut self, keyword: Keyword) -> Self {
+        self.attributes.keywords.push(keyword);
+        self
+    }
+
+    pub fn enters_tapped(mut self) -> Self {
+        self.attributes.enters_tapped = true;
+        self
+    }
+}
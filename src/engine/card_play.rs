@@ -1,8 +1,11 @@
 use std::fmt::Debug;
 
+use dyn_clone::DynClone;
+use serde::{Serialize, Deserialize};
+
 use super::prelude::*;
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Copy, Debug, Hash)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Copy, Debug, Hash, Serialize, Deserialize)]
 pub struct AssignedCardPlay {
     pub card: CardID,
     pub card_play: CardPlayID,
@@ -16,19 +19,103 @@ impl AssignedCardPlay {
 
 pub type CardPlayID = ID<CardPlay>;
 
+/// The zones a card play can be used from, relative to the card's owner.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum PlayZone {
+    Hand,
+    Graveyard,
+    Exile,
+    /// Only the top card of the library
+    LibraryTop,
+    Command,
+    /// Exile, but only for a card whose adventure resolved.
+    Adventure,
+}
+
+impl PlayZone {
+    pub fn zone(self, owner: PlayerID) -> Zone {
+        match self {
+            Self::Hand => Zone::Hand(owner),
+            Self::Graveyard => Zone::Graveyard(owner),
+            Self::Exile | Self::Adventure => Zone::Exile,
+            Self::LibraryTop => Zone::Deck(owner),
+            Self::Command => Zone::Command(owner),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct CardPlay {
     pub description: String,
     pub spawn: Box<dyn Spawner>,
     pub speed: AbilitySpeed,
+    pub zones: Vec<PlayZone>,
+    /// Where the card goes when it leaves the stack, instead of its
+    /// owner's graveyard. Flashback exiles the card, for example. An
+    /// adventure only goes on its adventure if it resolves.
+    pub leaves_stack_to: Option<PlayZone>,
+    /// Which face of the card is cast or played.
+    pub face: usize,
 }
 impl CardPlay {
     pub fn new(spawn: Box<dyn Spawner>, description: String, speed: AbilitySpeed) -> Self {
-        Self{spawn, description, speed}
+        Self{spawn, description, speed, zones: vec![PlayZone::Hand], leaves_stack_to: None, face: 0}
+    }
+
+    pub fn from_zones(mut self, zones: Vec<PlayZone>) -> Self {
+        self.zones = zones;
+        self
+    }
+
+    pub fn with_face(mut self, face: usize) -> Self {
+        self.face = face;
+        self
+    }
+
+    pub fn leaves_stack_to(mut self, zone: PlayZone) -> Self {
+        self.leaves_stack_to = Some(zone);
+        self
     }
 }
 
-pub trait Spawner {
-    fn spawn(&self, card: CardID, game: &Game) -> Object;
-    fn cost(&self, card_id: CardID, game: &Game) -> Cost;
+pub trait Spawner: DynClone + Send + Sync {
+    fn spawn(&self, as_card_play: AssignedCardPlay, game: &Game) -> Object;
+    fn cost(&self, as_card_play: AssignedCardPlay, game: &Game) -> Cost;
+
+    /// The legal choices for each of the spell's targets. 
+    fn targets(&self, _as_card_play: AssignedCardPlay, _game: &Game) -> TargetRequirements {
+        vec![]
+    }
+
+    /// Called when an instant or sorcery spawned by this resolves. 
+    /// Permanent spells don't need this, so a default implementation is provided
+    fn resolve(&self, _spell: &Object, _game: &mut Game) {}
+
+    /// The modes to choose from while casting, if the spell is modal.
+    /// Most spells aren't, so a default implementation is provided
+    fn modes(&self, _card_id: CardID, _game: &Game) -> Option<ModeRequirements> {
+        None
+    }
+
+    /// The legal choices for each of the targets of the given mode.
+    fn mode_targets(&self, _mode: usize, _card_id: CardID, _game: &Game) -> TargetRequirements {
+        vec![]
+    }
+
+    /// Called once for each chosen mode, in printed order, when a modal
+    /// spell resolves. `resolve` isn't called for modal spells.
+    fn resolve_mode(&self, _mode: &ChosenMode, _spell: &Object, _game: &mut Game) {}
+}
+
+dyn_clone::clone_trait_object!(Spawner);
+
+/// What one mode of a modal spell does when it resolves.
+pub trait SpellEffect: DynClone + Send + Sync {
+    fn targets(&self, _card_id: CardID, _game: &Game) -> TargetRequirements {
+        vec![]
+    }
+
+    fn resolve(&self, spell: &Object, targets: &[Target], game: &mut Game);
 }
 
+dyn_clone::clone_trait_object!(SpellEffect);
@@ -1,13 +1,16 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use rand::{Rng, seq::SliceRandom};
+use serde::{Serialize, Deserialize};
 use super::prelude::*;
 
 
 
-#[derive(Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Eq, PartialEq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize)]
 pub enum Zone {
     Hand(PlayerID),
     Graveyard(PlayerID),
     Deck(PlayerID),
+    Command(PlayerID),
     Exile,
     Battlefield,
     Stack,
@@ -17,9 +20,15 @@ pub enum Zone {
 /// This keeps track of ordering as well. For some zones such as
 /// battlefield and exile this doesn't matter, but for deck this 
 /// matters.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CardStore {
     id_to_card: BTreeMap<CardID, (Card, Zone)>,
-    zone_to_cards: BTreeMap<Zone, Vec<CardID>> 
+    zone_to_cards: BTreeMap<Zone, Vec<CardID>>,
+    /// Players who know what a card in a hidden zone is, because it was 
+    /// revealed to them or they looked at it. Forgotten once it changes zones.
+    revealed_to: BTreeMap<CardID, BTreeSet<PlayerID>>,
+    /// Cards exiled face down
+    face_down: BTreeSet<CardID>,
 }
 
 impl CardStore {
@@ -27,6 +36,8 @@ impl CardStore {
         let mut ret = Self {
             id_to_card: BTreeMap::new(),
             zone_to_cards: BTreeMap::new(),
+            revealed_to: BTreeMap::new(),
+            face_down: BTreeSet::new(),
         };
 
         use Zone::*;
@@ -39,6 +50,7 @@ impl CardStore {
             ret.zone_to_cards.insert(Hand(*player), vec![]);
             ret.zone_to_cards.insert(Graveyard(*player), vec![]);
             ret.zone_to_cards.insert(Deck(*player), vec![]);
+            ret.zone_to_cards.insert(Command(*player), vec![]);
         }
 
         ret
@@ -74,37 +86,145 @@ impl CardStore {
 
     /// Move the card corresponding to the given id to the given zone
     pub fn move_to_zone(&mut self, id: CardID, zone: Zone) {
-        let card = self.take_card(id);
+        let card = self.take_card_to(id, zone);
         self.put_card(card, zone);
     }
 
     fn take_card(&mut self, id: CardID) -> Card {
-        let card = self.id_to_card.remove(&id).expect("Card id doesn't exist");
+        // A card that changes zones is a new object nobody knows about yet
+        self.revealed_to.remove(&id);
+        self.face_down.remove(&id);
+
+        let mut card = self.id_to_card.remove(&id).expect("Card id doesn't exist");
         let cards = self.zone_to_cards.get_mut(&card.1).expect("zone doesn't exist");
         let pos = cards.iter().position(|&card_id| card_id == id).expect("card store fields are inconsistent");
         cards.remove(pos);
+        card.0.on_adventure = false;
         card.0
     }
 
+    /// Takes the card out of its zone to put it into the given one. Moving
+    /// it within the same zone, like reordering a library, keeps what the
+    /// players know about it.
+    fn take_card_to(&mut self, id: CardID, zone: Zone) -> Card {
+        let same_zone = self.get_zone(id) == zone;
+        let known = self.revealed_to.remove(&id).filter(|_| same_zone);
+        let card = self.take_card(id);
+        if let Some(players) = known {
+            self.revealed_to.insert(id, players);
+        }
+        card
+    }
+
     pub fn hand(&self, id: PlayerID) -> Vec<&Card> {
-        self.get_cards(Zone::Deck(id))
+        self.get_cards(Zone::Hand(id))
+    }
+
+    /// Every card in the game, in any zone.
+    pub fn all_cards(&self) -> impl Iterator<Item = &Card> {
+        self.id_to_card.values().map(|(card, _)| card)
+    }
+
+    /// Every card the player owns, in any zone.
+    pub fn owned_by(&self, id: PlayerID) -> Vec<&Card> {
+        self.id_to_card
+            .values()
+            .filter(|(card, _)| card.owner == id)
+            .map(|(card, _)| card)
+            .collect()
+    }
+
+    /// The card on top of the player's library, which is the next one drawn.
+    pub fn top_of_library(&self, id: PlayerID) -> Option<CardID> {
+        self.zone_to_cards.get(&Zone::Deck(id))?.last().copied()
+    }
+
+    /// The top cards of the player's library, starting with the top one.
+    pub fn library_top(&self, id: PlayerID, amount: usize) -> Vec<CardID> {
+        self.zone_to_cards
+            .get(&Zone::Deck(id))
+            .map(|library| library.iter().rev().take(amount).copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Puts the card on top of its owner's library.
+    pub fn put_on_top(&mut self, id: CardID) {
+        let owner = self.get_card(id).owner;
+        self.move_to_zone(id, Zone::Deck(owner));
+    }
+
+    /// Puts the card on the bottom of its owner's library.
+    pub fn put_on_bottom(&mut self, id: CardID) {
+        let zone = Zone::Deck(self.get_card(id).owner);
+        let card = self.take_card_to(id, zone);
+        self.zone_to_cards.get_mut(&zone).unwrap().insert(0, card.id);
+        self.id_to_card.insert(card.id, (card, zone));
     }
 
     /// Moves card from player's deck to the player's hand (if possible)
     /// returns id of drawn card. If you couldn't draw, returns None.
     pub fn draw(&mut self, id: PlayerID) -> Option<CardID> {
-        let maybe_card_id = self.zone_to_cards.get_mut(&Zone::Deck(id)).unwrap().pop();
-        if let Some(card_id) = maybe_card_id {
-            self.zone_to_cards.get_mut(&Zone::Hand(id)).unwrap().push(card_id);
-            Some(card_id)
-        } else {
-            None
+        let card_id = self.top_of_library(id)?;
+        self.move_to_zone(card_id, Zone::Hand(id));
+        Some(card_id)
+    }
+
+    /// Shuffling makes everyone forget what they knew about the library.
+    pub fn shuffle<R: Rng>(&mut self, id: PlayerID, rng: &mut R) {
+        if let Some(deck) = self.zone_to_cards.get_mut(&Zone::Deck(id)) {
+            deck.shuffle(rng);
+            for card in deck.iter() {
+                self.revealed_to.remove(card);
+            }
         }
     }
 
-    pub fn shuffle(&mut self, id: PlayerID) {
-        let deck = self.zone_to_cards.get_mut(&Zone::Deck(id));
-        todo!() //blah, randomness
+    /// Lets the players see the card until it changes zones.
+    pub fn reveal(&mut self, id: CardID, players: &[PlayerID]) {
+        self.revealed_to.entry(id).or_default().extend(players.iter().copied());
+    }
+
+    pub fn exile_face_down(&mut self, id: CardID) {
+        self.move_to_zone(id, Zone::Exile);
+        self.face_down.insert(id);
+    }
+
+    /// Exiles the card after its adventure resolved.
+    pub fn go_on_adventure(&mut self, id: CardID) {
+        self.move_to_zone(id, Zone::Exile);
+        self.id_to_card.get_mut(&id).unwrap().0.on_adventure = true;
+    }
+
+    /// Whether the player knows what the card is. Hands are seen by their
+    /// owner, libraries and face down cards by nobody, and anything else 
+    /// by everyone, unless the card was revealed.
+    pub fn can_see(&self, player: PlayerID, id: CardID) -> bool {
+        let revealed = self.revealed_to.get(&id).is_some_and(|players| players.contains(&player));
+        match self.get_zone(id) {
+            Zone::Hand(owner) => owner == player || revealed,
+            Zone::Deck(_) => revealed,
+            Zone::Exile if self.face_down.contains(&id) => revealed,
+            _ => true,
+        }
+    }
+
+    /// Every player that can see the card.
+    pub fn visible_to(&self, id: CardID, players: &[PlayerID]) -> Vec<PlayerID> {
+        players.iter().copied().filter(|player| self.can_see(*player, id)).collect()
+    }
+
+    /// Removes every card the player owns from the game. Used when a 
+    /// player leaves the game. Their own zones stay behind, empty.
+    pub fn remove_player(&mut self, player: PlayerID) {
+        let owned: Vec<CardID> = self.id_to_card
+            .values()
+            .filter(|(card, _)| card.owner == player)
+            .map(|(card, _)| card.id)
+            .collect();
+
+        for card_id in owned {
+            self.take_card(card_id);
+        }
     }
 
     pub fn put_card(&mut self, card: Card, zone: Zone) {
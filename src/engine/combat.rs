@@ -0,0 +1,70 @@
+use serde::{Serialize, Deserialize};
+
+use super::prelude::*;
+
+/// What an attacking creature is attacking.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum AttackTarget {
+    Player(PlayerID),
+    Planeswalker(PermanentID),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attack {
+    pub attacker: PermanentID,
+    pub target: AttackTarget,
+    pub defending_player: PlayerID,
+    /// The creatures blocking the attacker, in the order it assigns
+    /// combat damage to them.
+    pub blockers: Vec<PermanentID>,
+}
+
+/// The attackers and blockers of the current combat.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Combat {
+    pub attacks: Vec<Attack>,
+}
+
+impl AttackTarget {
+    /// The player being attacked, directly or through their planeswalker.
+    pub fn defending_player(&self, game: &Game) -> PlayerID {
+        match self {
+            AttackTarget::Player(player) => *player,
+            AttackTarget::Planeswalker(perm) => game.get(*perm).owner,
+        }
+    }
+}
+
+impl Into<GameObjectID> for AttackTarget {
+    fn into(self) -> GameObjectID {
+        match self {
+            AttackTarget::Player(player) => GameObjectID::Player(player),
+            AttackTarget::Planeswalker(perm) => GameObjectID::Permanent(perm),
+        }
+    }
+}
+
+impl Combat {
+    pub fn attack(&self, attacker: PermanentID) -> Option<&Attack> {
+        self.attacks.iter().find(|attack| attack.attacker == attacker)
+    }
+
+    pub fn is_attacking(&self, perm: PermanentID) -> bool {
+        self.attack(perm).is_some()
+    }
+
+    pub fn is_blocking(&self, perm: PermanentID) -> bool {
+        self.attacks.iter().any(|attack| attack.blockers.contains(&perm))
+    }
+
+    /// Everyone who is being attacked.
+    pub fn defending_players(&self) -> Vec<PlayerID> {
+        let mut defending = vec![];
+        for attack in &self.attacks {
+            if !defending.contains(&attack.defending_player) {
+                defending.push(attack.defending_player);
+            }
+        }
+        defending
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use super::prelude::*;
+
+pub const COMMANDER_STARTING_LIFE: i32 = 40;
+/// Combat damage from a single commander that makes a player lose.
+pub const LETHAL_COMMANDER_DAMAGE: u32 = 21;
+/// Extra generic mana to cast a commander from the command zone, for each
+/// time it was cast from there before.
+pub const COMMANDER_TAX: usize = 2;
+
+const BASIC_LAND_TYPES: [(CardSubType, ManaType); 5] = [
+    ("Plains", ManaType::White),
+    ("Island", ManaType::Blue),
+    ("Swamp", ManaType::Black),
+    ("Mountain", ManaType::Red),
+    ("Forest", ManaType::Green),
+];
+
+/// A deck for the Commander format. The commander starts the game in the
+/// command zone, and the rest of the cards in the library.
+pub struct CommanderDeck {
+    pub commander: LatentCard,
+    pub cards: Vec<LatentCard>,
+}
+
+#[derive(Debug)]
+pub enum DeckError {
+    /// Only legendary creatures can be commanders.
+    NotACommander(String),
+    /// The card has colors outside of its commander's color identity.
+    OutsideColorIdentity(String),
+}
+
+/// Keeps track of each player's commander, and of what it has done over
+/// the game.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Commanders {
+    pub commanders: BTreeMap<PlayerID, CardID>,
+    /// How many times each commander was cast from the command zone.
+    pub casts: BTreeMap<CardID, usize>,
+    /// Combat damage each commander has dealt to each player.
+    pub damage: BTreeMap<(CardID, PlayerID), u32>,
+}
+
+impl CommanderDeck {
+    pub fn new(commander: LatentCard, cards: Vec<LatentCard>) -> Self {
+        Self { commander, cards }
+    }
+
+    /// Checks that the commander can be one, and that every card fits in
+    /// its color identity.
+    pub fn validate(&self) -> Result<(), DeckError> {
+        let type_line = &self.commander.attributes.type_line;
+        if !type_line.is(CardSuperType::Legendary) || !type_line.is(CardType::Creature) {
+            return Err(DeckError::NotACommander(self.commander.attributes.name.clone()))
+        }
+
+        let identity = self.commander.color_identity();
+        for card in &self.cards {
+            if card.color_identity().iter().any(|color| !identity.contains(color)) {
+                return Err(DeckError::OutsideColorIdentity(card.attributes.name.clone()))
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Commanders {
+    pub fn is_commander(&self, card: CardID) -> bool {
+        self.commanders.values().any(|commander| *commander == card)
+    }
+
+    /// The extra generic mana it costs to cast the commander from the
+    /// command zone.
+    pub fn tax(&self, card: CardID) -> usize {
+        self.casts.get(&card).map_or(0, |casts| casts * COMMANDER_TAX)
+    }
+
+    pub fn cast_from_command_zone(&mut self, card: CardID) {
+        *self.casts.entry(card).or_default() += 1;
+    }
+
+    pub fn deal_damage(&mut self, commander: CardID, player: PlayerID, amount: u32) {
+        *self.damage.entry((commander, player)).or_default() += amount;
+    }
+
+    /// The most combat damage any one commander has dealt to the player.
+    pub fn most_damage_to(&self, player: PlayerID) -> u32 {
+        self.damage
+            .iter()
+            .filter(|((_, damaged), _)| *damaged == player)
+            .map(|(_, amount)| *amount)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Attributes {
+    // TODO mana symbols in rules text are part of the color identity too
+    /// The colors of the mana symbols in the cost, along with the colors
+    /// of any basic land types.
+    pub fn color_identity(&self) -> Vec<ManaType> {
+        let land_colors = BASIC_LAND_TYPES
+            .iter()
+            .filter(|(land_type, _)| self.type_line.is(*land_type))
+            .map(|(_, color)| *color);
+
+        let mut identity = vec![];
+        for color in self.colors().into_iter().chain(land_colors) {
+            if !identity.contains(&color) {
+                identity.push(color);
+            }
+        }
+        identity
+    }
+}
+
+impl LatentCard {
+    /// The color identity of every face of the card combined.
+    pub fn color_identity(&self) -> Vec<ManaType> {
+        let mut identity = self.attributes.color_identity();
+        for face in &self.other_faces {
+            for color in face.attributes.color_identity() {
+                if !identity.contains(&color) {
+                    identity.push(color);
+                }
+            }
+        }
+        identity
+    }
+}
@@ -1,8 +1,11 @@
+use serde::{Serialize, Deserialize};
+
+use super::prelude::*;
 
 /// This is a basic representation of counters.
 /// this does not approach a good solution.
 /// but for now it will work.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Counters {
     pub plus_one_plus_one: u32,
     pub min_one_min_one: u32,
@@ -19,4 +22,20 @@ impl Counters {
             lore: 0,
         }
     }
+
+    pub fn with_loyalty(mut self, loyalty: u32) -> Self {
+        self.loyalty = loyalty;
+        self
+    }
+
+    /// The count for the given kind of counter, if a permanent can have it.
+    pub fn count_mut(&mut self, counter_type: &CounterType) -> Option<&mut u32> {
+        match counter_type {
+            CounterType::PlusOnePlusOne => Some(&mut self.plus_one_plus_one),
+            CounterType::MinusOneMinusOne => Some(&mut self.min_one_min_one),
+            CounterType::Loyalty => Some(&mut self.loyalty),
+            CounterType::Lore => Some(&mut self.lore),
+            CounterType::Poison => None,
+        }
+    }
 }
@@ -0,0 +1,45 @@
+use serde::{Serialize, Deserialize};
+
+use super::prelude::*;
+
+/// A one-time triggered ability created by a resolving spell or ability,
+/// such as "at the beginning of the next end step, sacrifice it". Once it
+/// triggers, it is removed from the game's registry.
+///
+/// Only the ability that created it is kept, and that ability makes its
+/// trigger and effect again when they are needed, so it can be saved.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DelayedTrigger {
+    /// The ability that created this delayed trigger.
+    pub source: AssignedAbility,
+    pub controller: PlayerID,
+    /// The permanent it is about, like the token it sacrifices.
+    pub subject: PermanentID,
+}
+
+impl DelayedTrigger {
+    pub fn new(source: AssignedAbility, controller: PlayerID, subject: PermanentID) -> Self {
+        Self {
+            source,
+            controller,
+            subject,
+        }
+    }
+
+    /// The trigger and effect, as made by the source ability.
+    pub fn parts(&self, game: &Game) -> Option<(Box<dyn Trigger>, Box<dyn OneShot>)> {
+        game.get(self.source.ability)
+            .base
+            .class
+            .one_shot()
+            .and_then(|effect| effect.delayed_trigger(self.subject))
+    }
+
+    /// Whether the event sets off this delayed trigger. Checks the
+    /// intervening if clause as well.
+    pub fn triggers_on(&self, event: &GameEvent, game: &Game) -> bool {
+        let Some((trigger, _)) = self.parts(game) else { return false };
+        trigger.triggers_on(self.source.ability, self.source.perm, event, game)
+            && trigger.condition(self.source.ability, self.source.perm, game)
+    }
+}
@@ -1,12 +1,12 @@
 use std::{
     collections::BTreeMap, 
-    process::exit,
     fmt::Debug,
+    sync::Arc,
 };
 
 use super::{prelude::*, state_based_actions::check_state_based_actions};
 
-use crate::client::{Client, PlayerAction, GameStateSnapshot};
+use crate::client::{PlayerAction, PlayerInterface, Disconnected, GameStateSnapshot};
 
 use super::util::id::{
     ID,
@@ -15,82 +15,154 @@ use super::util::id::{
 };
 
 use log::{info, warn, error};
-use tokio::sync::broadcast::{channel, Sender as BroadcastSender};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Serialize, Deserialize};
 
 #[derive(Debug)]
 pub enum FailureReason {
     CouldntPayCost,
-    IllegalAbilityClass
+    IllegalAbilityClass,
+    NoLegalTargets,
+    Cancelled,
+    UnexpectedAction,
+    NoLandPlaysLeft,
+}
+
+/// The restore point from the last time a player received priority. It 
+/// holds a whole copy of the game state, so clones of the game start 
+/// without one.
+#[derive(Default)]
+struct PriorityPoint(Option<RestorePoint>);
+
+impl Clone for PriorityPoint {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+/// A copy of the game from when a player received priority, so the game
+/// can go back to it. Abilities and card plays are shared with the game,
+/// which makes the copy cheap.
+#[derive(Clone)]
+pub struct RestorePoint {
+    /// The player who had priority
+    pub player: PlayerID,
+    game: Box<Game>,
 }
 
 /// The Game object contains all game information
 /// and additionally acts as a store for all game
 /// objects so that they are easily found and rusts
 /// borrow checker doesn't get too mad.
+///
+/// Cloning a game is cheap, abilities and card plays are shared between
+/// the clones until one of them changes. Clones don't keep the restore
+/// point for undoing, and can be sent to other threads.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
-    pub player_with_last_action: PlayerID,
+    /// How many players have passed priority in a row, without anyone
+    /// taking an action in between.
+    pub passes_in_a_row: usize,
     pub active_player: PlayerID,
     pub turn_step: TurnStep,
 
     pub players: Vec<Player>,
     pub turn_number: usize,
-    pub game_over: bool,
-    pub event_stack: Vec<GameEvent>,
+    pub result: GameResult,
+    /// Players who have lost, but whose objects haven't been removed yet.
+    leaving_players: Vec<PlayerID>,
+    pub event_stack: Vec<QueuedEvent>,
     pub game_stack: Vec<Object>,
     pub cards: CardStore,
 
     pub battlefield: BTreeMap<PermanentID, Permanent>,
-    pub abilities: BTreeMap<AbilityID, Ability>,
-    pub card_plays: BTreeMap<CardPlayID, CardPlay>,
-
-
+    /// Saved games make these again from the cards' definitions.
+    #[serde(skip)]
+    pub abilities: Arc<BTreeMap<AbilityID, Ability>>,
+    #[serde(skip)]
+    pub card_plays: Arc<BTreeMap<CardPlayID, CardPlay>>,
+    pub delayed_triggers: Vec<DelayedTrigger>,
+    /// Tokens that left the battlefield. They cease to exist the next
+    /// time state based actions are checked.
+    pub tokens_elsewhere: Vec<Permanent>,
+    pub combat: Combat,
+    pub commanders: Commanders,
+    pub config: GameConfig,
+    timestamp: usize,
+    /// The random number generator started from this, so the same seed
+    /// and decisions always play out the same game.
+    pub seed: u64,
+    rng: ChaCha12Rng,
+
+
+    pub card_ids: IDFactory<CardID>,
     pub perm_ids: IDFactory<PermanentID>,
+    pub object_ids: IDFactory<ObjectID>,
     pub ability_ids: IDFactory<AbilityID>,
     pub card_play_ids: IDFactory<CardPlayID>,
 
-    // send game updates to clients
-    state_update_sender: BroadcastSender<GameStateSnapshot>,
-    client: Client,
+    /// Saved the last time a player received priority.
+    #[serde(skip)]
+    priority_point: PriorityPoint,
+    /// Where the game is saved each time a player receives priority.
+    #[serde(skip)]
+    autosave: Option<String>,
+
+    #[serde(skip, default = "default_interface")]
+    interface: Box<dyn PlayerInterface>,
 }
 
+/// Until an interface is set, nobody is there to make decisions.
+fn default_interface() -> Box<dyn PlayerInterface> {
+    Box::new(Disconnected)
+}
 
-impl Game {
-    pub fn new(decks: Vec<Vec<LatentCard>>) -> Self {
 
-        let card_ids = IDFactory::new();
+impl Game {
+    pub fn new(decks: Vec<Vec<LatentCard>>, config: GameConfig) -> Self {
         let player_ids = IDFactory::new()
             .take(decks.len())
             .collect::<Vec<_>>();
         let active_player = *player_ids.first().expect("Need at least on player in the game");
-
-        let cap = 1;
-        let (state_update_sender, state_update_receiver) = channel(cap);
-
-        let client = Client::launch(active_player, state_update_receiver).expect("unable to launch client");
+        let seed = rand::random();
 
         let mut game = Self {
             active_player,
-            player_with_last_action: active_player,
+            passes_in_a_row: 0,
             turn_step: TurnStep::Upkeep,
 
             players: Vec::new(),
             turn_number: 0,
-            game_over: false,
+            result: GameResult::new(),
+            leaving_players: vec![],
             event_stack: vec![],
 
 
             game_stack: Vec::new(),
-            card_plays: BTreeMap::new(),
+            card_plays: Arc::new(BTreeMap::new()),
+            delayed_triggers: vec![],
+            tokens_elsewhere: vec![],
+            combat: Combat::default(),
+            commanders: Commanders::default(),
+            config,
+            timestamp: 0,
+            seed,
+            rng: ChaCha12Rng::seed_from_u64(seed),
             battlefield: BTreeMap::new(),
-            abilities: BTreeMap::new(),
+            abilities: Arc::new(BTreeMap::new()),
             cards: CardStore::new(&player_ids),
 
+            card_ids: IDFactory::new(),
             perm_ids: IDFactory::new(),
+            object_ids: IDFactory::new(),
             ability_ids: IDFactory::new(),
             card_play_ids: IDFactory::new(),
 
-            state_update_sender,
-            client,
+            priority_point: PriorityPoint::default(),
+            autosave: None,
+
+            interface: default_interface(),
         };
 
         let players : Vec<_> = decks
@@ -98,30 +170,10 @@ impl Game {
             .zip(player_ids)
             .map(|(deck, player_id)| {
                 deck.into_iter().for_each(|base| {
-                    let card_id = card_ids.get_id();
-                    let  LatentCard {attributes, perm_abilities, card_plays} = base;
-
-                    let perm_ability_ids = perm_abilities
-                        .into_iter()
-                        .map(|ability| game.add_ability(ability))
-                        .collect();
-
-                    let card_play_ids = card_plays
-                        .into_iter()
-                        .map(|card_play| game.add_card_play(card_play))
-                        .collect();
-
-                    let card = Card::new(
-                        attributes, 
-                        card_id, 
-                        perm_ability_ids, 
-                        card_play_ids,
-                        player_id
-                    );
-
+                    let card = game.add_card(base, player_id);
                     game.cards.put_card(card, Zone::Deck(player_id));
                 });
-                Player::new(player_id)
+                Player::new(player_id, game.config.starting_life)
         }).collect();
 
         game.players = players;
@@ -129,11 +181,104 @@ impl Game {
         game
     }
 
+    /// Starts a Commander game. Each player starts with their commander
+    /// in the command zone, and at 40 life whatever the config says.
+    pub fn commander(decks: Vec<CommanderDeck>, config: GameConfig) -> Result<Self, DeckError> {
+        for deck in &decks {
+            deck.validate()?;
+        }
+
+        let (commanders, decks): (Vec<_>, Vec<_>) = decks
+            .into_iter()
+            .map(|deck| (deck.commander, deck.cards))
+            .unzip();
+        let config = GameConfig {
+            format: Format::Commander,
+            starting_life: COMMANDER_STARTING_LIFE,
+            ..config
+        };
+        let mut game = Self::new(decks, config);
+
+        let player_ids: Vec<_> = game.players.iter().map(|player| player.id).collect();
+        for (commander, player) in commanders.into_iter().zip(player_ids) {
+            let card = game.add_card(commander, player);
+            game.commanders.commanders.insert(player, card.id);
+            game.cards.put_card(card, Zone::Command(player));
+        }
+        Ok(game)
+    }
+
+    /// Registers the card's abilities and card plays with the game.
+    fn add_card(&mut self, base: LatentCard, owner: PlayerID) -> Card {
+        let LatentCard {definition, attributes, perm_abilities, card_plays, other_faces, layout} = base;
+
+        let front_face = LatentFace { attributes, perm_abilities };
+        let faces = std::iter::once(front_face)
+            .chain(other_faces)
+            .map(|face| Face {
+                attrs: face.attributes,
+                perm_abilities: face.perm_abilities
+                    .into_iter()
+                    .map(|ability| self.add_ability(ability))
+                    .collect(),
+            })
+            .collect();
+
+        let card_play_ids = card_plays
+            .into_iter()
+            .map(|card_play| self.add_card_play(card_play))
+            .collect();
+
+        Card::new(
+            definition,
+            faces, 
+            layout,
+            self.card_ids.get_id(), 
+            card_play_ids,
+            owner
+        )
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.rng = ChaCha12Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Connects the game to its players.
+    pub fn with_interface(mut self, interface: Box<dyn PlayerInterface>) -> Self {
+        self.interface = interface;
+        self
+    }
+
+    pub fn set_interface(&mut self, interface: Box<dyn PlayerInterface>) {
+        self.interface = interface;
+    }
+
+    /// Saves the game to the file each time a player receives priority,
+    /// so it can be stopped at any time and resumed later.
+    pub fn with_autosave(mut self, path: String) -> Self {
+        self.autosave = Some(path);
+        self
+    }
+
     /// Queues an event to be processed by the game. Keep in mind that 
     /// events are processed in a Last-in First-out order.
     pub fn push_event(&mut self, event: GameEvent) {
         info!("   Event pushed {:?}", event);
-        self.event_stack.push(event);
+        self.event_stack.push(QueuedEvent::new(event));
+    }
+
+    /// Queues events that replaced another event. They remember which 
+    /// replacement effects have already been applied.
+    fn push_replaced_events(&mut self, events: Vec<GameEvent>, applied_replacements: Vec<AssignedAbility>) {
+        for event in events {
+            info!("   Replacement event pushed {:?}", event);
+            self.event_stack.push(QueuedEvent {
+                event,
+                applied_replacements: applied_replacements.clone(),
+            });
+        }
     }
 
     pub fn push_events<I: IntoIterator<Item = GameEvent>>(&mut self, events: I) {
@@ -149,19 +294,60 @@ impl Game {
 
             DrawCard(player_id) => {
                 let drawn_card = self.cards.draw(player_id);
-                // Couldn't draw a card, lose the game.
+                // Couldn't draw a card, lose the game the next time state
+                // based actions are checked.
                 if drawn_card.is_none() { 
-                    self.push_event(Lose(player_id, GameRule::CouldntDraw.into()));
+                    self.get_mut(player_id).drew_from_empty_library = true;
+                }
+            }
+
+            OpeningHand(player) => self.draw_opening_hand(player),
+
+            DiscardCard(card, _) => {
+                let owner = self.get(card).owner;
+                if self.cards.get_zone(card) != Zone::Hand(owner) {
+                    warn!("Tried to discard {:?}, but it isn't in its owner's hand", card);
+                    return
                 }
+                self.move_card(card, Zone::Graveyard(owner));
             }
 
             UntapPerm(perm_id) => {
                 self.battlefield.get_mut(&perm_id).unwrap().untap()
             }
 
-            Lose(player_id, _reason) => {
-                println!("player {:?} lost.", player_id);
-                exit(0);
+            Lose(player_id, reason) => {
+                if self.get(player_id).eliminated { return }
+                if !self.win_lose(player_id).can_lose {
+                    info!("Player {:?} can't lose the game", player_id);
+                    return
+                }
+                // Teams lose together
+                for teammate in self.teammates(player_id) {
+                    self.eliminate(teammate, reason.clone());
+                }
+            }
+
+            Win(player_id, reason) => {
+                if self.get(player_id).eliminated { return }
+                if !self.win_lose(player_id).can_win {
+                    info!("Player {:?} can't win the game", player_id);
+                    return
+                }
+
+                info!("Player {:?} won the game: {:?}", player_id, reason);
+                let team = self.teammates(player_id);
+                for opponent in self.remaining_players() {
+                    if team.contains(&opponent) { continue }
+                    if !self.win_lose(opponent).can_lose {
+                        info!("Player {:?} can't lose the game", opponent);
+                        continue
+                    }
+                    self.eliminate(opponent, GameRule::OpponentWon.into());
+                }
+                for teammate in team {
+                    self.result.winners.push((teammate, reason.clone()));
+                }
             }
 
             RegisterPermanent(perm) => {
@@ -173,95 +359,413 @@ impl Game {
                         warn!("Non-token permanent has no card {:?}", perm);
                     }
                 }
+                let mut perm = perm;
+                perm.timestamp = self.next_timestamp();
+                let is_saga = perm.type_line.is(SAGA);
+                // If its host is gone, it enters unattached, and state based
+                // actions put an Aura into the graveyard.
+                match perm.attached_to.and_then(|host| self.battlefield.get_mut(&host)) {
+                    Some(host) => host.attachments.push(id),
+                    None => perm.attached_to = None,
+                }
                 self.battlefield.insert(id, perm);
                 self.push_event(EnterTheBattleField(id));
+
+                // Sagas enter with a lore counter
+                if is_saga {
+                    self.push_event(AddCounters(GameObjectID::Permanent(id), CounterType::Lore, 1, GameRule::SagaLore.into()));
+                }
             }
 
             AddMana(player_id, mana_type, _) => {
                 self.get_mut(player_id).mana_pool.push(mana_type);
             }
 
-            PlaySpell(as_card_play) => {
+            PlaySpell(as_card_play, targets, modes, x) => {
                 let card_play = self.get(as_card_play.card_play);
-                let object = card_play.spawn.spawn(as_card_play.card, self);
+                let mut object = card_play.spawn.spawn(as_card_play, self);
+                object.targets = targets;
+                object.modes = modes;
+                object.x = x;
+                object.leaves_stack_to = card_play.leaves_stack_to;
 
                 if let ObjectResolve::CreateLand(perm) = object.resolve {
                     // Lands skip the stack and enter the battlefield directly
+                    self.get_mut(object.controller).lands_played += 1;
                     self.push_event(RegisterPermanent(perm));
                 } else {
+                    if let Zone::Command(_) = self.cards.get_zone(as_card_play.card) {
+                        self.commanders.cast_from_command_zone(as_card_play.card);
+                    }
                     self.game_stack.push(object);
                     self.cards.move_to_zone(as_card_play.card, Zone::Stack);
                 }
             }
 
-            ActivateAbility(as_ability) => {
-                // TODO: this is not the best way to do this; removing the ability from
-                // the map the get around the borrow checker. 
-                // a better solution in which abilities are not stored in the game object,
-                // or the abilities not being able to modify the game directly
+            ActivateAbility(as_ability, targets, modes) => {
                 // TODO: activated abilities should go on the stack, not be run directly
-                let ability = self.abilities.remove(&as_ability.ability).expect("Invalid ability ID should not be possible");
+                // Abilities are shared, so holding on to them doesn't borrow the game.
+                let abilities = Arc::clone(&self.abilities);
+                let ability = abilities.get(&as_ability.ability).expect("Invalid ability ID should not be possible");
                 match ability.base.class {
-                    AbilityClass::Activated(_, ref ability) => ability.activate(as_ability.ability, as_ability.perm, self),
+                    AbilityClass::Activated(ref cost, ref ability) => {
+                        if cost.is_loyalty() {
+                            self.get_mut(as_ability.perm).loyalty_activated = true;
+                        }
+                        if modes.is_empty() {
+                            ability.activate(as_ability.ability, as_ability.perm, &targets, self)
+                        }
+                        for mode in modes {
+                            ability.activate_mode(mode.mode, as_ability.ability, as_ability.perm, &mode.targets, self)
+                        }
+                    }
                     _ => {
                         error!("Expected activated ability for ActivateAbility event {:?}", as_ability); 
                         panic!();
                     }
                 }
-                self.abilities.insert(as_ability.ability, ability);
             }
 
             EnterTheBattleField(_) => {}
-            LegendConflict(_) => todo!(),
-            Destroy(_, _) => todo!(),
-            Sacrifice(_, _) => todo!(),
-            AddCounters(_, _, _, _) => todo!(),
-            RemoveCounters(_, _, _, _) => todo!(),
+            LegendConflict(perms) => {
+                // The controller chooses one to keep, the rest go to the graveyard
+                let Some(player) = perms.first().map(|perm| self.get(*perm).owner) else { return };
+                let choices = perms.iter().map(|perm| Target::Permanent(*perm)).collect();
+                let keep = match self.choose_targets(player, vec![choices]).as_deref() {
+                    Ok([Target::Permanent(keep)]) => *keep,
+                    _ => perms[0],
+                };
 
+                let events = perms
+                    .into_iter()
+                    .filter(|perm| *perm != keep)
+                    .map(|perm| PutInGraveyard(perm, GameRule::LegendRule.into()))
+                    .collect();
+                self.push_event(Simultaneously(events));
+            }
+
+            Simultaneously(events) => {
+                // Only reached when nested in another batch of events.
+                self.push_events(events);
+            }
+            Destroy(perm_id, _) | Sacrifice(perm_id, _) | PutInGraveyard(perm_id, _) => {
+                self.put_in_graveyard(perm_id);
+            }
+
+            CeaseToExist(token, _) => {
+                self.tokens_elsewhere.retain(|other| other.id != token);
+            }
+
+            Attach(attachment, host, _) => {
+                if !self.battlefield.contains_key(&host) || !self.battlefield.contains_key(&attachment) {
+                    info!("Can't attach {:?} to {:?}, one of them left the battlefield", attachment, host);
+                    return
+                }
+                self.unattach(attachment);
+                self.get_mut(attachment).attached_to = Some(host);
+                self.get_mut(host).attachments.push(attachment);
+            }
+
+            Unattach(attachment, _) => {
+                self.unattach(attachment);
+            }
+
+            Transform(perm_id, _) => {
+                let Some(card) = self.get(perm_id).card else { return };
+                if self.get(card).layout != CardLayout::Transform {
+                    warn!("{:?} can't transform", perm_id);
+                    return
+                }
+
+                let face = 1 - self.get(perm_id).face;
+                let card = self.cards.get_card(card);
+                self.battlefield.get_mut(&perm_id).unwrap().set_face(card, face);
+            }
+
+            Flip(perm_id, _) => {
+                let Some(card) = self.get(perm_id).card else { return };
+                if self.get(card).layout != CardLayout::Flip || self.get(perm_id).face != 0 {
+                    warn!("{:?} can't flip", perm_id);
+                    return
+                }
+
+                let card = self.cards.get_card(card);
+                self.battlefield.get_mut(&perm_id).unwrap().set_face(card, 1);
+            }
+
+            AddCounters(object, counter_type, amount, _) => {
+                let Some(count) = self.counter_mut(&object, &counter_type) else { return };
+                let before = *count;
+                *count += amount;
+
+                if let (GameObjectID::Permanent(perm_id), CounterType::Lore) = (object, counter_type) {
+                    self.trigger_chapters(perm_id, before, before + amount);
+                }
+            }
+
+            RemoveCounters(object, counter_type, amount, _) => {
+                if let Some(count) = self.counter_mut(&object, &counter_type) {
+                    *count = count.saturating_sub(amount);
+                }
+            }
+
+            DealDamage(GameObjectID::Permanent(perm_id), amount, source) => {
+                let deathtouch = match source {
+                    EventSource::Permanent(source) | EventSource::Combat(source) => self.battlefield.get(&source)
+                        .is_some_and(|source| source.has_keyword(Keyword::Deathtouch)),
+                    _ => false,
+                };
+
+                let perm = self.get_mut(perm_id);
+                if perm.type_line.is(CardType::Creature) {
+                    perm.damage += amount as i32;
+                    perm.deathtouch_damage |= deathtouch && amount > 0;
+                }
+                // Damage dealt to a planeswalker removes that much loyalty
+                if perm.type_line.is(CardType::Planeswalker) {
+                    self.push_event(RemoveCounters(GameObjectID::Permanent(perm_id), CounterType::Loyalty, amount, source));
+                }
+            }
+
+            DealDamage(GameObjectID::Player(player_id), amount, source) => {
+                // Teammates share their life total
+                for teammate in self.teammates(player_id) {
+                    self.get_mut(teammate).life_total -= amount as i32;
+                }
+
+                if let EventSource::Combat(source) = source {
+                    let commander = self.battlefield
+                        .get(&source)
+                        .and_then(|perm| perm.card)
+                        .filter(|card| self.commanders.is_commander(*card));
+                    if let Some(commander) = commander {
+                        self.commanders.deal_damage(commander, player_id, amount);
+                    }
+                }
+            }
+
+            DealDamage(object, _, _) => {
+                warn!("{:?} can't be dealt damage", object);
+            }
+
+            GainLife(player_id, amount, _) => {
+                if self.observe_player(player_id).can_gain_life {
+                    for teammate in self.teammates(player_id) {
+                        self.get_mut(teammate).life_total += amount as i32;
+                    }
+                }
+            }
+
+            BoostUntilEndOfTurn(perm_id, power, toughness, _) => {
+                // Cleaning up at the end of the turn takes it away again
+                let pt = self.battlefield.get_mut(&perm_id).and_then(|perm| perm.power_toughness.as_mut());
+                if let Some(pt) = pt {
+                    pt.power += power;
+                    pt.toughness += toughness;
+                }
+            }
+
+
+            GivePriority(player) if self.get(player).eliminated => {
+                self.push_event(GivePriority(self.next_player(player)));
+            }
 
             GivePriority(player) => {
-                self.player_with_last_action = player;
                 self.try_give_priority(player);
             }
 
             PassPriority(player) => {
-                if player == self.player_with_last_action {
+                self.passes_in_a_row += 1;
+                if self.passes_in_a_row < self.remaining_players().len() {
+                    self.push_event(GivePriority(self.next_player(player)));
+                    return
+                }
+
+                // Everyone passed in succession. The top object of the stack
+                // resolves, or if it is empty, the step ends; the next step
+                // was queued up when this one started.
+                self.passes_in_a_row = 0;
+                if !self.game_stack.is_empty() {
                     self.push_event(TryResolveStackObject)
-                } else {
-                    let ability_order = self.build_ability_order();
-                    self.priority(player, ability_order);
                 }
             },
 
             TryResolveStackObject =>  {
                 if let Some(object) = self.game_stack.pop() {
                     self.push_event(GivePriority(self.active_player));
+
+                    // A spell whose targets have all become illegal doesn't resolve,
+                    // like when they gained hexproof or left the controller's range
+                    let all_targets_illegal = object.all_targets().next().is_some()
+                        && !object.all_targets().any(|target| self.is_targetable(object.controller, *target));
+                    if all_targets_illegal {
+                        info!("All targets are illegal, object doesn't resolve");
+                        self.remove_spell_card(&object, false);
+                        return
+                    }
+
                     match object.resolve {
-                        ObjectResolve::CreatePerm(perm) => {
+                        ObjectResolve::CreatePerm(mut perm) => {
+                            // Aura spells enter attached to what they targeted
+                            if perm.is_aura() {
+                                perm.attached_to = object.targets.iter().find_map(|target| match target {
+                                    Target::Permanent(host) => Some(*host),
+                                    _ => None,
+                                });
+                            }
+
                             self.push_event(RegisterPermanent(perm));
 
                             if let Some(card) = object.card {
                                 self.cards.move_to_zone(card, Zone::Battlefield);
                             }
                         }
-                        ObjectResolve::AbilityActivate(_) => todo!(),
+                        ObjectResolve::Spell(as_card_play) => {
+                            let card_plays = Arc::clone(&self.card_plays);
+                            let card_play = card_plays.get(&as_card_play.card_play).expect("Invalid card play ID should not be possible");
+                            if object.modes.is_empty() {
+                                card_play.spawn.resolve(&object, self);
+                            }
+                            for mode in object.modes.iter() {
+                                card_play.spawn.resolve_mode(mode, &object, self);
+                            }
+                            self.remove_spell_card(&object, true);
+                        }
+                        ObjectResolve::AbilityActivate(as_ability) => self.resolve_ability(as_ability, &object.targets),
+                        ObjectResolve::DelayedTrigger(delayed) => self.resolve_delayed_trigger(delayed, &object.targets),
                         ObjectResolve::CreateLand(perm) => error!("Land was on the stack, this shouldn't happen. {:?}", perm),
                     }
                 }
             }
 
+            CounterObject(object_id, _) => {
+                if !self.query(CounterableQuery::new(object_id), &AbilityOrdering::build_from(self)).can_be_countered {
+                    info!("{:?} can't be countered", object_id);
+                    return
+                }
+
+                let Some(idx) = self.game_stack.iter().position(|object| object.id == object_id) else {
+                    warn!("Tried to counter {:?}, but it isn't on the stack", object_id);
+                    return
+                };
+
+                let object = self.game_stack.remove(idx);
+                self.remove_spell_card(&object, false);
+            }
+
+            Scry(player, amount) => {
+                let cards = self.cards.library_top(player, amount as usize);
+                self.look_at(player, &cards);
+                info!("Asking {:?} to choose cards to put on the bottom", player);
+                let bottom = self.choose_cards(player, cards.clone(), cards.len());
+                for card in bottom.iter() {
+                    self.cards.put_on_bottom(*card);
+                }
+
+                let rest = cards.into_iter().filter(|card| !bottom.contains(card)).collect();
+                self.reorder_library_top(player, rest);
+            }
+
+            Surveil(player, amount) => {
+                let cards = self.cards.library_top(player, amount as usize);
+                self.look_at(player, &cards);
+                info!("Asking {:?} to choose cards to put into the graveyard", player);
+                let milled = self.choose_cards(player, cards.clone(), cards.len());
+                for card in milled.iter() {
+                    self.move_card(*card, Zone::Graveyard(player));
+                }
+
+                let rest = cards.into_iter().filter(|card| !milled.contains(card)).collect();
+                self.reorder_library_top(player, rest);
+            }
+
+            Mill(player, amount) => {
+                for card in self.cards.library_top(player, amount as usize) {
+                    self.move_card(card, Zone::Graveyard(player));
+                }
+            }
+
+            SearchLibrary(search) => {
+                let player = search.player;
+                let library: Vec<_> = self.cards.get_cards(Zone::Deck(player))
+                    .into_iter()
+                    .map(|card| card.id)
+                    .collect();
+                // The searcher sees their whole library until it is shuffled
+                self.look_at(player, &library);
+                let candidates = library
+                    .into_iter()
+                    .filter(|card| search.filter.matches(self.get(*card).attrs()))
+                    .collect();
+
+                // The library is hidden, so the player may fail to find anything
+                info!("Asking {:?} to search their library", player);
+                let found = self.choose_cards(player, candidates, 1).pop();
+
+                // Events are processed last in first out, so the shuffle is pushed 
+                // before what has to happen after it
+                match (found, search.destination) {
+                    (Some(card), SearchDestination::LibraryTop) => {
+                        self.push_event(PutOnTop(card, EventSource::Player(player)));
+                        self.push_event(Shuffle(player));
+                    }
+                    (Some(card), SearchDestination::Battlefield | SearchDestination::BattlefieldTapped) => {
+                        self.push_event(Shuffle(player));
+                        let mut perm = Permanent::from_card(self.get(card), self.perm_ids.get_id(), player);
+                        perm.tapped |= search.destination == SearchDestination::BattlefieldTapped;
+                        self.push_event(RegisterPermanent(perm));
+                    }
+                    (Some(card), SearchDestination::Hand) => {
+                        self.push_event(Shuffle(player));
+                        self.move_card(card, Zone::Hand(player));
+                    }
+                    (None, _) => self.push_event(Shuffle(player)),
+                }
+
+                if let (Some(card), true) = (found, search.reveal) {
+                    self.push_event(Reveal(vec![card], self.remaining_players()));
+                }
+            }
+
+            Reveal(cards, players) => {
+                for card in cards {
+                    info!("{:?} revealed to {:?}", self.get(card).attrs().name, players);
+                    self.cards.reveal(card, &players);
+                }
+            }
+
+            PutOnTop(card, _) | PutOnBottom(card, _) if self.cards.get_zone(card) == Zone::Battlefield => {
+                warn!("Tried to put {:?} into a library directly from the battlefield", card);
+            }
+
+            PutOnTop(card, _) if self.to_command_zone(card) => {}
+            PutOnTop(card, _) => self.cards.put_on_top(card),
+            PutOnBottom(card, _) if self.to_command_zone(card) => {}
+            PutOnBottom(card, _) => self.cards.put_on_bottom(card),
+
+            Shuffle(player) => self.cards.shuffle(player, &mut self.rng),
+
+            ExileCard(card, _) | ExileFaceDown(card, _) if matches!(self.cards.get_zone(card), Zone::Battlefield | Zone::Stack) => {
+                warn!("Tried to exile {:?} directly from the battlefield or stack", card);
+            }
+
+            ExileCard(card, _) | ExileFaceDown(card, _) if self.to_command_zone(card) => {}
+            ExileCard(card, _) => self.cards.move_to_zone(card, Zone::Exile),
+            ExileFaceDown(card, _) => self.cards.exile_face_down(card),
+
             NextStep => {
-                let current_step = DEFAULT_TURN_STRUCTURE
+                let turn_structure = &self.config.turn_structure;
+                let current_step = turn_structure
                     .iter()
                     .enumerate()
                     .find(|turn| self.turn_step == *turn.1)
                     .expect("Current Turn should be in turn structure.").0;
                 let next_step = current_step + 1;
 
-                let next_step_event = if next_step == DEFAULT_TURN_STRUCTURE.len() {
+                let next_step_event = if next_step == turn_structure.len() {
                     StartTurn(self.next_player(self.active_player))
                 } else {
-                    Step(DEFAULT_TURN_STRUCTURE[next_step])
+                    Step(turn_structure[next_step])
                 };
 
                 if self.event_stack.len() != 0 {
@@ -272,9 +776,63 @@ impl Game {
 
             StartTurn(player) => {
                 self.active_player = player;
-                self.push_event(Step(DEFAULT_TURN_STRUCTURE[0]))
+                self.turn_number += 1;
+                let team = self.teammates(player);
+                for player in self.players.iter_mut() {
+                    player.lands_played = 0;
+                }
+                for perm in self.battlefield.values_mut() {
+                    perm.loyalty_activated = false;
+                    // The player has now controlled their permanents since
+                    // the start of their turn.
+                    if team.contains(&perm.owner) {
+                        perm.summoning_sickness = false;
+                    }
+                }
+                self.push_event(Step(self.config.turn_structure[0]))
             },
 
+            DeclareAttackers(player) => {
+                let attacks = self.choose_attackers(player)
+                    .into_iter()
+                    .map(|(attacker, target)| Attacks(attacker, target))
+                    .collect();
+                self.push_event(Simultaneously(attacks));
+            }
+
+            Attacks(attacker, target) => {
+                if self.combat.is_attacking(attacker) {
+                    warn!("{:?} is already attacking", attacker);
+                    return
+                }
+                let defending_player = target.defending_player(self);
+                let perm = self.get_mut(attacker);
+                if !perm.has_keyword(Keyword::Vigilance) {
+                    perm.tapped = true;
+                }
+                self.combat.attacks.push(Attack { attacker, target, defending_player, blockers: vec![] });
+            }
+
+            DeclareBlockers(player) => {
+                let blocks = self.choose_blockers(player)
+                    .into_iter()
+                    .map(|(blocker, attacker)| Blocks(blocker, attacker))
+                    .collect();
+                self.push_event(Simultaneously(blocks));
+            }
+
+            Blocks(blocker, attacker) => {
+                match self.combat.attacks.iter_mut().find(|attack| attack.attacker == attacker) {
+                    Some(attack) => attack.blockers.push(blocker),
+                    None => warn!("{:?} can't block {:?}, it isn't attacking", blocker, attacker),
+                }
+            }
+
+            CombatDamage => {
+                let damage = self.combat_damage();
+                self.push_event(Simultaneously(damage));
+            }
+
             TapPerm(perm) => {
                 let perm = self.get_mut(perm);
                 if perm.tapped {
@@ -303,6 +861,395 @@ impl Game {
         }
     }
 
+    /// Moves the card of a spell that left the stack without becoming a
+    /// permanent. It goes to its owner's graveyard, unless it was cast in
+    /// a way that says otherwise.
+    fn remove_spell_card(&mut self, object: &Object, resolved: bool) {
+        let Some(card) = object.card else { return };
+        let owner = self.get(card).owner;
+        match object.leaves_stack_to {
+            Some(PlayZone::Adventure) if resolved => {
+                if !self.to_command_zone(card) {
+                    self.cards.go_on_adventure(card);
+                }
+            }
+            // A countered adventure goes to the graveyard like anything else
+            Some(PlayZone::Adventure) | None => self.move_card(card, Zone::Graveyard(owner)),
+            Some(zone) => self.move_card(card, zone.zone(owner)),
+        }
+    }
+
+    /// Removes the permanent from the battlefield, putting its card into
+    /// its owner's graveyard. Tokens go there too, until state based 
+    /// actions make them cease to exist.
+    fn put_in_graveyard(&mut self, perm_id: PermanentID) {
+        self.unattach(perm_id);
+        let Some(perm) = self.battlefield.remove(&perm_id) else {
+            warn!("Tried to put {:?} into the graveyard, but it isn't on the battlefield", perm_id);
+            return
+        };
+
+        if perm.is_token {
+            self.tokens_elsewhere.push(perm);
+        } else if let Some(card) = perm.card {
+            let owner = self.get(card).owner;
+            self.move_card(card, Zone::Graveyard(owner));
+        }
+    }
+
+    /// Moves the card to another zone, unless it is a commander and its
+    /// owner puts it into the command zone instead. Every zone change 
+    /// but casting, resolving and drawing should go through this.
+    fn move_card(&mut self, card: CardID, zone: Zone) {
+        if !self.to_command_zone(card) {
+            self.cards.move_to_zone(card, zone);
+        }
+    }
+
+    /// If the card is a commander about to go to another zone, its owner
+    /// may put it into the command zone instead. Returns whether they did.
+    fn to_command_zone(&mut self, card: CardID) -> bool {
+        if !self.commanders.is_commander(card) { return false }
+
+        let owner = self.get(card).owner;
+        let description = format!("Put {} into the command zone", self.get(card).attrs().name);
+        info!("Asking {:?} whether to put their commander into the command zone", owner);
+        match self.interface.choose_options(owner, vec![PlayerAction::Pass, PlayerAction::Card(card, description)]) {
+            PlayerAction::Card(chosen, _) if chosen == card => {
+                self.cards.move_to_zone(card, Zone::Command(owner));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Detaches the aura or equipment from whatever it is attached to.
+    /// Its attachments stay pointed at it until state based actions
+    /// deal with them.
+    fn unattach(&mut self, attachment: PermanentID) {
+        let Some(perm) = self.battlefield.get_mut(&attachment) else { return };
+        let Some(host) = perm.attached_to.take() else { return };
+
+        if let Some(host) = self.battlefield.get_mut(&host) {
+            host.attachments.retain(|id| *id != attachment);
+        }
+    }
+
+    /// A short description of a spell or ability on the stack.
+    pub fn describe_object(&self, id: ObjectID) -> String {
+        let Some(object) = self.game_stack.iter().find(|object| object.id == id) else {
+            return format!("{:?}", id)
+        };
+
+        match (object.card, &object.resolve) {
+            (Some(card), _) => self.get(card).attrs().name.clone(),
+            (None, ObjectResolve::AbilityActivate(as_ability)) => self.get(as_ability.ability).base.description.clone(),
+            (None, ObjectResolve::DelayedTrigger(delayed)) => format!("Delayed trigger from {:?}", delayed.source),
+            _ => format!("{:?}", id),
+        }
+    }
+
+    pub fn is_legal_target(&self, target: &Target) -> bool {
+        match target {
+            Target::Permanent(perm) => self.battlefield.contains_key(perm),
+            Target::Player(player) => !self.get(*player).eliminated,
+            Target::Object(object) => self.game_stack.iter().any(|stack_object| stack_object.id == *object),
+        }
+    }
+
+    /// Asks the player to choose one target for each requirement.
+    fn choose_targets(&mut self, player: PlayerID, requirements: TargetRequirements) -> Result<Vec<Target>, FailureReason> {
+        let mut targets = vec![];
+        for choices in requirements {
+            let choices: Vec<_> = choices
+                .into_iter()
+                .filter(|target| self.is_targetable(player, *target))
+                .collect();
+            if choices.is_empty() {
+                Err(FailureReason::NoLegalTargets)?;
+            }
+
+            let mut options = vec![PlayerAction::Pass];
+            options.extend(choices.into_iter().map(|target| {
+                let description = match target {
+                    Target::Permanent(perm) => self.get(perm).name.clone(),
+                    Target::Player(player) => format!("{:?}", player),
+                    Target::Object(object) => self.describe_object(object),
+                };
+                PlayerAction::Target(target, description)
+            }));
+
+            info!("Asking {:?} to choose a target", player);
+            match self.interface.choose_options(player, options) {
+                PlayerAction::Target(target, _) => targets.push(target),
+                _ => Err(FailureReason::Cancelled)?,
+            }
+        }
+        Ok(targets)
+    }
+
+    /// Asks the player to choose up to `max` of the cards, one at a time.
+    /// They can stop choosing early by passing.
+    fn choose_cards(&mut self, player: PlayerID, mut cards: Vec<CardID>, max: usize) -> Vec<CardID> {
+        let mut chosen = vec![];
+        while chosen.len() < max && !cards.is_empty() {
+            let mut options = vec![PlayerAction::Pass];
+            options.extend(cards.iter().map(|card| PlayerAction::Card(*card, self.describe_card(player, *card))));
+
+            match self.interface.choose_options(player, options) {
+                PlayerAction::Card(card, _) if cards.contains(&card) => {
+                    cards.retain(|other| *other != card);
+                    chosen.push(card);
+                }
+                _ => break,
+            }
+        }
+        chosen
+    }
+
+    /// Like `choose_cards`, but the player can't choose fewer. If they
+    /// stop early, the first of the remaining cards are chosen for them.
+    fn choose_exactly(&mut self, player: PlayerID, cards: Vec<CardID>, amount: usize) -> Vec<CardID> {
+        let mut chosen = self.choose_cards(player, cards.clone(), amount);
+        let rest: Vec<_> = cards.into_iter().filter(|card| !chosen.contains(card)).collect();
+        chosen.extend(rest.into_iter().take(amount - chosen.len()));
+        chosen
+    }
+
+    /// Draws the player a new hand until they keep one, following the
+    /// game's mulligan rules.
+    fn draw_opening_hand(&mut self, player: PlayerID) {
+        let hand_size = self.config.starting_hand_size;
+        let mut mulligans = 0;
+        loop {
+            for card in self.cards.hand(player).iter().map(|card| card.id).collect::<Vec<_>>() {
+                self.cards.put_on_top(card);
+            }
+            self.cards.shuffle(player, &mut self.rng);
+
+            let draws = match self.config.mulligan {
+                MulliganType::London => hand_size,
+                MulliganType::Paris | MulliganType::Vancouver => hand_size.saturating_sub(mulligans),
+            };
+            for _ in 0..draws {
+                self.cards.draw(player);
+            }
+
+            let options = vec![PlayerAction::Pass, PlayerAction::Mulligan];
+            info!("Asking {:?} whether to keep their hand of {} card(s)", player, draws);
+            match self.interface.choose_options(player, options) {
+                PlayerAction::Mulligan if draws > 0 => mulligans += 1,
+                _ => break,
+            }
+        }
+
+        match self.config.mulligan {
+            MulliganType::London if mulligans > 0 => {
+                let hand: Vec<_> = self.cards.hand(player).iter().map(|card| card.id).collect();
+                info!("Asking {:?} to put {} card(s) on the bottom of their library", player, mulligans);
+                for card in self.choose_exactly(player, hand, mulligans.min(hand_size)) {
+                    self.cards.put_on_bottom(card);
+                }
+            }
+            MulliganType::Vancouver if mulligans > 0 => self.push_event(GameEvent::Scry(player, 1)),
+            _ => {}
+        }
+    }
+
+    fn look_at(&mut self, player: PlayerID, cards: &[CardID]) {
+        for card in cards {
+            self.cards.reveal(*card, &[player]);
+        }
+    }
+
+    /// The card's name, if the player can see it.
+    pub fn describe_card(&self, player: PlayerID, card: CardID) -> String {
+        if self.cards.can_see(player, card) {
+            self.get(card).attrs().name.clone()
+        } else {
+            "Hidden card".into()
+        }
+    }
+
+    /// Lets the player put the cards back on top of their library in any
+    /// order. Cards they don't choose keep their order, below the chosen ones.
+    fn reorder_library_top(&mut self, player: PlayerID, cards: Vec<CardID>) {
+        info!("Asking {:?} to order the top of their library, starting with the top card", player);
+        let mut order = self.choose_cards(player, cards.clone(), cards.len());
+        order.extend(cards.into_iter().filter(|card| !order.contains(card)).collect::<Vec<_>>());
+
+        // The last card put on top ends up on top
+        for card in order.into_iter().rev() {
+            self.cards.put_on_top(card);
+        }
+    }
+
+    /// Asks the player to choose modes, and then the targets of each 
+    /// chosen mode. Returns nothing if there are no modes to choose.
+    fn choose_modes<F>(&mut self, player: PlayerID, requirements: Option<ModeRequirements>, mode_targets: F) -> Result<Vec<ChosenMode>, FailureReason>
+    where F: Fn(usize, &Game) -> TargetRequirements {
+        let Some(requirements) = requirements else { return Ok(vec![]) };
+
+        // Modes that need targets can't be chosen if there are none
+        let legal_modes: Vec<_> = (0..requirements.descriptions.len())
+            .filter(|mode| mode_targets(*mode, self)
+                .iter()
+                .all(|choices| choices.iter().any(|target| self.is_targetable(player, *target))))
+            .collect();
+        if legal_modes.is_empty() && requirements.min > 0 {
+            Err(FailureReason::NoLegalTargets)?;
+        }
+
+        let mut modes = vec![];
+        while modes.len() < requirements.max {
+            let mut options = vec![PlayerAction::Pass];
+            options.extend(requirements.descriptions
+                .iter()
+                .enumerate()
+                .filter(|(mode, _)| legal_modes.contains(mode))
+                .filter(|(mode, _)| requirements.repeatable || !modes.contains(mode))
+                .map(|(mode, description)| PlayerAction::Mode(mode, description.clone())));
+
+            info!("Asking {:?} to choose a mode", player);
+            match self.interface.choose_options(player, options) {
+                PlayerAction::Mode(mode, _) => modes.push(mode),
+                _ if modes.len() >= requirements.min => break,
+                _ => Err(FailureReason::Cancelled)?,
+            }
+        }
+        // Modes are always performed in the order they are printed
+        modes.sort();
+
+        modes
+            .into_iter()
+            .map(|mode| {
+                let requirements = mode_targets(mode, self);
+                Ok(ChosenMode::new(mode, self.choose_targets(player, requirements)?))
+            })
+            .collect()
+    }
+
+    /// Puts every chapter ability whose number was just reached on the stack.
+    fn trigger_chapters(&mut self, perm_id: PermanentID, before: u32, after: u32) {
+        let order = AbilityOrdering::build_from(self);
+        let controller = self.get(perm_id).owner;
+
+        let triggered: Vec<_> = self.perm_abilities(perm_id, &order)
+            .into_iter()
+            .filter(|as_ability| self.get(as_ability.ability).base.class
+                .chapter()
+                .is_some_and(|chapter| before < chapter && chapter <= after))
+            .collect();
+
+        for as_ability in triggered {
+            info!("Chapter ability {:?} triggered", as_ability);
+            self.game_stack.push(Object::from_ability(as_ability, controller, self.object_ids.get_id()));
+        }
+    }
+
+    /// Resolves an ability that was put on the stack.
+    fn resolve_ability(&mut self, as_ability: AssignedAbility, targets: &[Target]) {
+        // TODO abilities should resolve using last known information
+        if !self.battlefield.contains_key(&as_ability.perm) {
+            warn!("Source of {:?} left the battlefield, it does nothing", as_ability);
+            return
+        }
+
+        let abilities = Arc::clone(&self.abilities);
+        let ability = abilities.get(&as_ability.ability).expect("Invalid ability ID should not be possible");
+        match ability.base.class {
+            AbilityClass::Chapter(_, ref effect) => effect.activate(as_ability.ability, as_ability.perm, targets, self),
+            AbilityClass::StackTriggered(ref trigger, ref effect) => {
+                if trigger.condition(as_ability.ability, as_ability.perm, self) {
+                    effect.activate(as_ability.ability, as_ability.perm, targets, self)
+                } else {
+                    info!("Condition of {:?} is no longer true, it does nothing", as_ability);
+                }
+            }
+            _ => error!("Expected an ability that uses the stack for {:?}", as_ability),
+        }
+    }
+
+    fn resolve_delayed_trigger(&mut self, delayed: DelayedTrigger, targets: &[Target]) {
+        let source = delayed.source;
+        let Some((trigger, effect)) = delayed.parts(self) else {
+            error!("{:?} doesn't make the delayed trigger it created", source);
+            return
+        };
+        if trigger.condition(source.ability, source.perm, self) {
+            effect.activate(source.ability, source.perm, targets, self);
+        } else {
+            info!("Condition of delayed trigger from {:?} is no longer true, it does nothing", source);
+        }
+    }
+
+    /// Registers a one-time triggered ability, created by a resolving effect.
+    pub fn add_delayed_trigger(&mut self, delayed: DelayedTrigger) {
+        info!("Delayed trigger created by {:?}", delayed.source);
+        self.delayed_triggers.push(delayed);
+    }
+
+    /// Collects the triggered abilities and delayed triggers that the event
+    /// sets off. Delayed triggers that fire are removed from the registry.
+    fn collect_stack_triggers(&mut self, event: &GameEvent, order: &AbilityOrdering) -> Vec<Object> {
+        let mut objects: Vec<_> = order
+            .stack_triggers(event, self)
+            .into_iter()
+            .map(|as_ability| Object::from_ability(as_ability, self.get(as_ability.perm).owner, self.object_ids.get_id()))
+            .collect();
+
+        let (fired, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.delayed_triggers)
+            .into_iter()
+            .partition(|delayed| delayed.triggers_on(event, self));
+        self.delayed_triggers = waiting;
+        objects.extend(fired.into_iter().map(|delayed| Object::from_delayed_trigger(delayed, self.object_ids.get_id())));
+
+        objects
+    }
+
+    fn counter_mut(&mut self, object: &GameObjectID, counter_type: &CounterType) -> Option<&mut u32> {
+        let count = match (object, counter_type) {
+            (GameObjectID::Permanent(perm), _) => self.get_mut(*perm).counters.count_mut(counter_type),
+            (GameObjectID::Player(player), CounterType::Poison) => Some(&mut self.get_mut(*player).poison_counters),
+            _ => None,
+        };
+
+        if count.is_none() {
+            warn!("{:?} can't have {:?} counters", object, counter_type);
+        }
+        count
+    }
+
+    fn eliminate(&mut self, player_id: PlayerID, reason: EventSource) {
+        info!("Player {:?} lost the game: {:?}", player_id, reason);
+        self.get_mut(player_id).eliminated = true;
+        self.result.losers.push((player_id, reason));
+        self.leaving_players.push(player_id);
+    }
+
+    pub fn win_lose(&self, player: PlayerID) -> WinLoseQuery {
+        self.query(WinLoseQuery::new(player), &AbilityOrdering::build_from(self))
+    }
+
+    /// The permanent as effects change it, with its current power and
+    /// toughness.
+    pub fn observe_perm(&self, perm: PermanentID, order: &AbilityOrdering) -> Permanent {
+        self.query(ObservePermQuery::new(self.get(perm).clone()), order).perm
+    }
+
+    pub fn observe_player(&self, player: PlayerID) -> ObservePlayerQuery {
+        self.query(ObservePlayerQuery::new(player), &AbilityOrdering::build_from(self))
+    }
+
+    pub fn is_targetable(&self, player: PlayerID, target: Target) -> bool {
+        self.query(TargetableQuery::new(player, target), &AbilityOrdering::build_from(self)).targetable
+    }
+
+    /// Whether the player has a land play left this turn.
+    pub fn can_play_land(&self, player: PlayerID) -> bool {
+        self.get(player).lands_played < self.observe_player(player).land_plays
+    }
+
     pub fn build_ability_order(&mut self) -> AbilityOrdering {
         AbilityOrdering::build_from(self)
     }
@@ -314,14 +1261,36 @@ impl Game {
             .collect()
     }
 
-    pub fn all_card_plays(&self, order: &AbilityOrdering) -> Vec<AssignedCardPlay> {
-        self.players
-            .iter()
-            .flat_map(|player| self.cards.hand(player.id).into_iter())
+    /// The card plays available for the player's own cards. Other 
+    /// players' cards are never looked at.
+    pub fn all_card_plays(&self, player: PlayerID, order: &AbilityOrdering) -> Vec<AssignedCardPlay> {
+        self.cards.owned_by(player)
+            .into_iter()
             .flat_map(|card| self.card_plays(card.id, order).into_iter())
             .collect()
     }
 
+    /// The characteristics of the face a card play casts.
+    pub fn spell_attrs(&self, as_card_play: AssignedCardPlay) -> &Attributes {
+        let face = self.get(as_card_play.card_play).face;
+        &self.get(as_card_play.card).face(face).attrs
+    }
+
+    /// Which zone a card can be played from, from its owner's point of view.
+    /// Cards in the library can only be played from the top.
+    pub fn play_zone(&self, card_id: CardID) -> Option<PlayZone> {
+        let owner = self.get(card_id).owner;
+        match self.cards.get_zone(card_id) {
+            Zone::Hand(_) => Some(PlayZone::Hand),
+            Zone::Graveyard(_) => Some(PlayZone::Graveyard),
+            Zone::Exile if self.get(card_id).on_adventure => Some(PlayZone::Adventure),
+            Zone::Exile => Some(PlayZone::Exile),
+            Zone::Command(_) => Some(PlayZone::Command),
+            Zone::Deck(_) if self.cards.top_of_library(owner) == Some(card_id) => Some(PlayZone::LibraryTop),
+            _ => None,
+        }
+    }
+
     pub fn card_plays(&self, card_id: CardID, order: &AbilityOrdering) -> Vec<AssignedCardPlay> {
         let card_plays = self.query(
             CardPlaysQuery::new(card_id), 
@@ -352,22 +1321,90 @@ impl Game {
         Q::try_from(query).expect("Game query variant changed.")
     }
 
-    fn raw_query(&self, query: &mut GameQuery, ability_order: &AbilityOrdering) {
-        match query {
-            GameQuery::PermAbilities(ref mut query) => {
-                let base_abilities = self
-                    .get(query.id)
-                    .abilities
-                    .iter();
-                query.abilities.extend(base_abilities);
+    fn raw_query(&self, query: &mut GameQuery, ability_order: &AbilityOrdering) {
+        match query {
+            GameQuery::PermAbilities(ref mut query) => {
+                let base_abilities = self
+                    .get(query.id)
+                    .abilities
+                    .iter();
+                query.abilities.extend(base_abilities);
+            }
+
+            GameQuery::CardPlays(ref mut query) => {
+                // Only the card plays that can be used from the card's zone
+                if let Some(zone) = self.play_zone(query.id) {
+                    // Commanders are cast from the command zone as if
+                    // they were in their owner's hand
+                    let zone = if zone == PlayZone::Command { PlayZone::Hand } else { zone };
+                    query.card_plays.extend(self.cards.get_card(query.id).card_plays
+                        .iter()
+                        .filter(|card_play| self.get(**card_play).zones.contains(&zone)));
+                }
+            }
+
+            GameQuery::ObservePerm(ref mut query) => {
+                // +1/+1 and -1/-1 counters change power and toughness
+                // before any static abilities do.
+                let counters = &query.perm.counters;
+                let boost = counters.plus_one_plus_one as i32 - counters.min_one_min_one as i32;
+                if let Some(ref mut pt) = query.perm.power_toughness {
+                    pt.power += boost;
+                    pt.toughness += boost;
+                }
+            }
+
+            GameQuery::WinLose(_) => {
+                // players can win and lose unless an effect says otherwise
+            }
+
+            GameQuery::Counterable(ref mut query) => {
+                let card = self.game_stack
+                    .iter()
+                    .find(|object| object.id == query.id)
+                    .and_then(|object| object.card);
+                if let Some(card) = card {
+                    query.can_be_countered &= !self.get(card).attrs().cant_be_countered;
+                }
+            }
+
+            GameQuery::Cost(ref mut query) => {
+                // Affinity makes the spell itself cost less
+                if let CostSource::CardPlay(as_card_play) = query.source {
+                    if let Some(ref card_type) = self.spell_attrs(as_card_play).affinity {
+                        let count = self.battlefield
+                            .values()
+                            .filter(|perm| perm.owner == query.player && perm.type_line.is(card_type.clone()))
+                            .count();
+                        query.reduction += count;
+                    }
+                }
+            }
+
+            GameQuery::ObservePlayer(ref mut query) => {
+                query.life_total = self.get(query.player).life_total;
+                query.hand_size = self.cards.hand(query.player).len();
+                query.max_hand_size = self.config.max_hand_size;
+                // One land each turn
+                query.land_plays = 1;
             }
 
-            GameQuery::CardPlays(ref mut query) => {
-                query.card_plays.extend(self.cards.get_card(query.id).card_plays.iter());
+            GameQuery::CanAttack(ref mut query) => {
+                let attacker = self.get(query.attacker);
+                query.can_attack &= attacker.type_line.is(CardType::Creature)
+                    && !attacker.tapped
+                    && !attacker.summoning_sickness;
             }
 
-            GameQuery::ObservePerm(ref mut query) => {
-                // TODO account for counters here
+            GameQuery::CanBlock(ref mut query) => {
+                let blocker = self.get(query.blocker);
+                // Creatures with flying can only be blocked by creatures with flying
+                let reaches = !self.get(query.attacker).has_keyword(Keyword::Flying) || blocker.has_keyword(Keyword::Flying);
+                query.can_block &= blocker.type_line.is(CardType::Creature) && !blocker.tapped && reaches;
+            }
+
+            GameQuery::Targetable(ref mut query) => {
+                query.targetable &= self.is_legal_target(&query.target) && self.can_reach(query.player, &query.target);
             }
         }
 
@@ -379,14 +1416,20 @@ impl Game {
         use GameEvent::*;
 
         self.turn_step = turn_step;
+        self.passes_in_a_row = 0;
         self.push_event(NextStep);
 
+        // Creatures stop attacking and blocking once combat is over
+        if !turn_step.is_combat() {
+            self.combat = Combat::default();
+        }
+
         // Whether or not this turn gives priority
         match turn_step {
             Untap => {
 
                let events: Vec<_> = self.battlefield.values().filter_map(|perm|
-                    (self.active_player == perm.owner && perm.tapped)
+                    (self.is_active(perm.owner) && perm.tapped)
                         .then_some(UntapPerm(perm.id))
                 ).collect();
 
@@ -394,18 +1437,62 @@ impl Game {
             }
 
             Upkeep => {
+                self.push_event(GivePriority(self.active_player));
             },
 
             Draw => {
                 self.push_event(GivePriority(self.active_player));
-                self.push_event(DrawCard(self.active_player));
+                // The player or team going first might skip their first draw
+                if self.turn_number > 1 || self.config.first_player_draws {
+                    let draws: Vec<_> = self.teammates(self.active_player).into_iter().map(DrawCard).collect();
+                    self.push_events(draws.into_iter().rev());
+                }
             }
 
             FirstMainPhase => {
                 self.push_event(GivePriority(self.active_player));
+
+                // The active player's sagas each get a lore counter
+                let events: Vec<_> = self.battlefield.values().filter_map(|perm|
+                    (self.is_active(perm.owner) && perm.type_line.is(SAGA))
+                        .then_some(AddCounters(
+                            GameObjectID::Permanent(perm.id), 
+                            CounterType::Lore, 
+                            1, 
+                            GameRule::SagaLore.into()))
+                ).collect();
+
+                self.push_events(events)
+            }
+
+            BeginCombat => {
+                self.push_event(GivePriority(self.active_player));
+            }
+
+            TurnStep::DeclareAttackers => {
+                self.push_event(GivePriority(self.active_player));
+                self.push_event(GameEvent::DeclareAttackers(self.active_player));
+            }
+
+            TurnStep::DeclareBlockers => {
+                self.push_event(GivePriority(self.active_player));
+
+                // Defending players declare blockers in APNAP order
+                let defending = self.combat.defending_players();
+                let events: Vec<_> = self.apnap_order()
+                    .into_iter()
+                    .filter(|player| defending.contains(player))
+                    .map(GameEvent::DeclareBlockers)
+                    .collect();
+                self.push_events(events.into_iter().rev());
+            }
+
+            TurnStep::CombatDamage => {
+                self.push_event(GivePriority(self.active_player));
+                self.push_event(GameEvent::CombatDamage);
             }
 
-            Combat =>  {
+            EndCombat => {
                 self.push_event(GivePriority(self.active_player));
             }
 
@@ -413,8 +1500,27 @@ impl Game {
                 self.push_event(GivePriority(self.active_player));
             }
 
+            End => {
+                self.push_event(GivePriority(self.active_player));
+            }
+
             Discard => {
-                // TODO implement this
+                let mut events = vec![];
+                for player in self.teammates(self.active_player) {
+                    let Some(max_hand_size) = self.observe_player(player).max_hand_size else { continue };
+                    let hand: Vec<_> = self.cards.hand(player).iter().map(|card| card.id).collect();
+                    if hand.len() <= max_hand_size { continue }
+
+                    let excess = hand.len() - max_hand_size;
+                    info!("Asking {:?} to discard {} card(s)", player, excess);
+                    let discarded = self.choose_exactly(player, hand, excess);
+                    events.extend(discarded
+                        .into_iter()
+                        .map(|card| DiscardCard(card, GameRule::MaxHandSize.into())));
+                }
+                if !events.is_empty() {
+                    self.push_event(Simultaneously(events));
+                }
             }
 
             CleanUp => {
@@ -424,6 +1530,8 @@ impl Game {
                         pt.toughness = pt.base_toughness;
                         pt.power = pt.base_power;
                     }
+                    perm.damage = 0;
+                    perm.deathtouch_damage = false;
                 }
             }
         }
@@ -433,19 +1541,59 @@ impl Game {
         // First check state based actions.
         let facade = GameFacade::new(self);
         let before_give_priority_events = check_state_based_actions(&facade);
+        let ability_order = facade.into();
+
+        // Only draws from an empty library since the last check count
+        for other in self.players.iter_mut() {
+            other.drew_from_empty_library = false;
+        }
+
+        // State based actions happen simultaneously, and are checked 
+        // again until there are none.
         if before_give_priority_events.len() > 0 {
             self.push_event(GameEvent::GivePriority(player));
-            self.push_events(before_give_priority_events);
+            self.push_event(GameEvent::Simultaneously(before_give_priority_events));
             return;
         }
 
         // if there were none, then give the player priority
-        self.priority(player, facade.into());
+        self.priority(player, ability_order);
     }
 
     pub fn priority(&mut self, player_id: PlayerID, ability_order: AbilityOrdering) {
+        // Every player is kept up to date, but only with what they can see.
+        for player in self.remaining_players() {
+            let snapshot = GameStateSnapshot::for_player(self, player);
+            self.interface.update_state(player, snapshot);
+        }
+
+        // Undoing goes back to the priority point before this one
+        let point = self.restore_point(player_id);
+        let mut undo_point = self.priority_point.0.replace(point);
+        if let Some(path) = &self.autosave {
+            // Resuming gives the player priority again
+            let mut saved = self.clone();
+            saved.push_event(GameEvent::GivePriority(player_id));
+            if let Err(reason) = saved.save(path) {
+                warn!("Couldn't save the game; {:?}", reason);
+            }
+        }
+
         loop {
-            let action = self.get_player_action(player_id, &ability_order);
+            let action = self.get_player_action(player_id, &ability_order, undo_point.is_some());
+
+            if let PlayerAction::Undo = action {
+                if let Some(point) = undo_point.take() {
+                    if self.everyone_agrees_to_undo(player_id) {
+                        self.restore(point);
+                        self.priority_point.0 = None;
+                        return
+                    }
+                    undo_point = Some(point);
+                }
+                info!("Undo didn't happen");
+                continue
+            }
 
             match self.try_do_player_action(player_id, action) {
                 Ok(()) => return,
@@ -454,45 +1602,124 @@ impl Game {
         }
     }
 
+    /// Saves the game as it is while the player has priority. Nobody makes
+    /// decisions for the copy.
+    pub fn restore_point(&mut self, player: PlayerID) -> RestorePoint {
+        let interface = std::mem::replace(&mut self.interface, default_interface());
+        let game = Box::new(self.clone());
+        self.interface = interface;
+        RestorePoint { player, game }
+    }
+
+    /// Puts the game back the way it was at the restore point. The player
+    /// who had priority then gets it again. The same interface keeps 
+    /// making decisions, and the game keeps saving to the same place.
+    pub fn restore(&mut self, point: RestorePoint) {
+        info!("Restoring the game to when {:?} had priority", point.player);
+        let mut game = *point.game;
+        std::mem::swap(&mut game.interface, &mut self.interface);
+        game.autosave = self.autosave.take();
+        *self = game;
+
+        self.push_event(GameEvent::GivePriority(point.player));
+    }
+
+    /// Asks everyone else whether they agree to undo the player's last action.
+    fn everyone_agrees_to_undo(&mut self, player: PlayerID) -> bool {
+        let others: Vec<_> = self.remaining_players()
+            .into_iter()
+            .filter(|other| *other != player)
+            .collect();
+
+        others.into_iter().all(|other| {
+            info!("Asking {:?} whether they agree to undo", other);
+            matches!(self.interface.choose_options(other, vec![PlayerAction::Pass, PlayerAction::Undo]), PlayerAction::Undo)
+        })
+    }
+
     fn try_do_player_action(&mut self, player_id: PlayerID, action: PlayerAction) -> Result<(), FailureReason> {
 
         match action {
             PlayerAction::Pass => {
-                self.push_event(GameEvent::PassPriority(self.next_player(player_id)));
+                self.push_event(GameEvent::PassPriority(player_id));
+                return Ok(())
             }
 
+            PlayerAction::Target(..) 
+            | PlayerAction::Mode(..) 
+            | PlayerAction::ChooseX(..)
+            | PlayerAction::Card(..)
+            | PlayerAction::Replacement(..)
+            | PlayerAction::Mulligan
+            | PlayerAction::Undo
+            | PlayerAction::Attack(..)
+            | PlayerAction::Block(..) => Err(FailureReason::UnexpectedAction)?,
+
 
             PlayerAction::CardPlay(as_card_play, _) => {
-                // TODO should be a query
-                let cost = self.get(as_card_play.card_play).spawn.cost(as_card_play.card, self);
-                let events = self.try_pay_cost(player_id, cost)?;
+                if self.is_land_play(as_card_play) && !self.can_play_land(player_id) {
+                    Err(FailureReason::NoLandPlaysLeft)?;
+                }
+
+                let spawn = &self.get(as_card_play.card_play).spawn;
+                let requirements = spawn.targets(as_card_play, self);
+                let mode_requirements = spawn.modes(as_card_play.card, self);
+                let cost = spawn.cost(as_card_play, self);
+
+                let modes = self.choose_modes(player_id, mode_requirements, |mode, game| 
+                    game.get(as_card_play.card_play).spawn.mode_targets(mode, as_card_play.card, game))?;
+                let x = self.choose_x(CostSource::CardPlay(as_card_play), player_id, &cost)?;
+                let targets = self.choose_targets(player_id, requirements)?;
+                let cost = self.total_cost(CostSource::CardPlay(as_card_play), player_id, x, cost);
+                let exiled = self.try_exile_from_graveyard(player_id, as_card_play.card, cost.exile_from_graveyard)?;
+                let mut events = self.try_pay_cost(player_id, cost)?;
+                events.extend(exiled);
 
                 self.push_event(GameEvent::GivePriority(player_id));
-                self.push_event(GameEvent::PlaySpell(as_card_play));
+                self.push_event(GameEvent::PlaySpell(as_card_play, targets, modes, x));
                 self.push_events(events);
             }
 
 
             PlayerAction::ActivateAbility(as_ability, _) => {
-                // TODO should be a query
-                let cost = match self.get(as_ability.ability).base.class {
-                    AbilityClass::Activated(ref cost, _) => cost.clone(),
+                let (cost, requirements, mode_requirements) = match self.get(as_ability.ability).base.class {
+                    AbilityClass::Activated(ref cost, ref effect) => (
+                        cost.clone(), 
+                        effect.targets(as_ability.ability, as_ability.perm, self),
+                        effect.modes(as_ability.ability, as_ability.perm, self),
+                    ),
                     _ => Err(FailureReason::IllegalAbilityClass)?,
                 };
 
+                let modes = self.choose_modes(player_id, mode_requirements, |mode, game| 
+                    match game.get(as_ability.ability).base.class {
+                        AbilityClass::Activated(_, ref effect) => effect.mode_targets(mode, as_ability.ability, as_ability.perm, game),
+                        _ => vec![],
+                    })?;
+                let targets = self.choose_targets(player_id, requirements)?;
+                let cost = AbilityCost {
+                    cost: self.total_cost(CostSource::Ability(as_ability), player_id, 0, cost.cost.clone()),
+                    ..cost
+                };
                 let events = self.try_pay_ability_cost(player_id, as_ability.perm, cost)?;
 
                 self.push_event(GameEvent::GivePriority(player_id));
-                self.push_event(GameEvent::ActivateAbility(as_ability));
+                self.push_event(GameEvent::ActivateAbility(as_ability, targets, modes));
                 self.push_events(events);
             }
         };
 
-            
+        // Taking an action starts a new round of passing
+        self.passes_in_a_row = 0;
         Ok(())
     }
 
     fn try_pay_ability_cost(&mut self, player: PlayerID, perm: PermanentID, cost: AbilityCost) -> Result<Vec<GameEvent>, FailureReason> {
+        if cost.is_sorcery_speed() && !self.can_play_sorceries(player) {
+            Err(FailureReason::IllegalAbilityClass)?;
+        }
+
+        let loyalty = cost.loyalty;
         let mut ret = self.try_pay_cost(player, cost.cost)?;
         let perm = self.get(perm);
         if cost.tap {
@@ -502,9 +1729,78 @@ impl Game {
                 ret.push(GameEvent::TapPerm(perm.id));
             }
         }
+
+        // Only one loyalty ability can be activated per planeswalker each turn.
+        if let Some(loyalty) = loyalty {
+            if perm.loyalty_activated {
+                Err(FailureReason::IllegalAbilityClass)?;
+            }
+
+            let object = GameObjectID::Permanent(perm.id);
+            let source = EventSource::Permanent(perm.id);
+            let amount = loyalty.unsigned_abs();
+            if loyalty >= 0 {
+                ret.push(GameEvent::AddCounters(object, CounterType::Loyalty, amount, source));
+            } else if perm.counters.loyalty >= amount {
+                ret.push(GameEvent::RemoveCounters(object, CounterType::Loyalty, amount, source));
+            } else {
+                Err(FailureReason::CouldntPayCost)?;
+            }
+        }
         Ok(ret)
     }
 
+    /// Asks the player to choose a value for X, if the cost has any.
+    /// They can choose at most what their mana pool can pay for, after
+    /// everything that makes the rest of the cost more or less.
+    fn choose_x(&mut self, source: CostSource, player: PlayerID, cost: &Cost) -> Result<u32, FailureReason> {
+        if cost.x == 0 { return Ok(0) }
+
+        let rest = self.total_cost(source, player, 0, cost.clone());
+        let available = self.get(player).mana_pool.len().saturating_sub(rest.total());
+        let max = (available / cost.x) as u32;
+
+        let mut options = vec![PlayerAction::Pass];
+        options.extend((0..=max).map(PlayerAction::ChooseX));
+
+        info!("Asking {:?} to choose X", player);
+        match self.interface.choose_options(player, options) {
+            PlayerAction::ChooseX(x) => Ok(x),
+            _ => Err(FailureReason::Cancelled),
+        }
+    }
+
+    /// The cost to actually pay, with X replaced by its value and every 
+    /// effect that makes it cost more or less applied.
+    pub fn total_cost(&self, source: CostSource, player: PlayerID, x: u32, cost: Cost) -> Cost {
+        let mut mana_cost = cost.mana_cost.clone().with_x_value(x);
+        if let CostSource::CardPlay(as_card_play) = source {
+            if let Zone::Command(_) = self.cards.get_zone(as_card_play.card) {
+                mana_cost.increase_generic(self.commanders.tax(as_card_play.card));
+            }
+        }
+        let cost = cost.with_mana(mana_cost);
+        self.query(CostQuery::new(source, player, x, cost), &AbilityOrdering::build_from(self)).total()
+    }
+
+    /// Exiles other cards from the player's graveyard as part of a cost.
+    fn try_exile_from_graveyard(&self, player: PlayerID, card: CardID, amount: usize) -> Result<Vec<GameEvent>, FailureReason> {
+        if amount == 0 { return Ok(vec![]) }
+
+        // TODO let the player choose which cards
+        let exiled: Vec<_> = self.cards.get_cards(Zone::Graveyard(player))
+            .into_iter()
+            .filter(|other| other.id != card)
+            .take(amount)
+            .map(|other| GameEvent::ExileCard(other.id, EventSource::Player(player)))
+            .collect();
+
+        if exiled.len() < amount {
+            Err(FailureReason::CouldntPayCost)?
+        }
+        Ok(exiled)
+    }
+
     fn try_pay_cost(&mut self, player: PlayerID, cost: Cost) -> Result<Vec<GameEvent>, FailureReason> {
         self.try_pay_mana_cost(player, cost.mana_cost)
     }
@@ -530,11 +1826,15 @@ impl Game {
         Ok(vec![GameEvent::PayMana(player, cost)])
     }
 
-    fn get_player_action(&mut self, player_id: PlayerID, ordering: &AbilityOrdering) -> PlayerAction {
+    fn get_player_action(&mut self, player_id: PlayerID, ordering: &AbilityOrdering, can_undo: bool) -> PlayerAction {
 
         let mut player_actions = vec![PlayerAction::Pass];
+        if can_undo {
+            player_actions.push(PlayerAction::Undo);
+        }
 
         let keep_sorcery_speed = self.can_play_sorceries(player_id);
+        let can_play_land = self.can_play_land(player_id);
 
         // collect all abilities, then sort into type and if the player controls the ability
         // TODO Sort based on speed! (not implemented for abilities rn)
@@ -545,6 +1845,7 @@ impl Game {
 
             match self.get(assigned_ability.ability).base.class {
                 AbilityClass::Activated(ref cost, _) => {
+                    if cost.is_sorcery_speed() && !keep_sorcery_speed { continue }
                     player_actions.push(
                         PlayerAction::ActivateAbility(
                             assigned_ability, 
@@ -555,23 +1856,20 @@ impl Game {
         }
 
         // collect all abilities, then sort into type and if the player controls the ability
-        for assigned_card_play in self.all_card_plays(ordering) {
-            let player = self.get(assigned_card_play.card).owner;
-
-            // only card plays for cards the current player owns
-            if player != player_id {continue}
+        for assigned_card_play in self.all_card_plays(player_id, ordering) {
             // only card plays that match the player's allowed speed
             if self.get(assigned_card_play.card_play).speed == AbilitySpeed::Sorcery && !keep_sorcery_speed { continue }
+            if self.is_land_play(assigned_card_play) && !can_play_land { continue }
 
             player_actions.push(
                 PlayerAction::CardPlay(assigned_card_play, self.get(assigned_card_play.card_play).description.clone())
             );
         }
-        return self.client.choose_options(player_actions)
+        return self.interface.choose_options(player_id, player_actions)
 
             /*
         // TODO implement checking of cost and payment + rejection if not good
-        let new_event = match self.client.choose_options(player_actions) {
+        let new_event = match self.interface.choose_options(player_id, player_actions) {
             PlayerAction::CardPlay(as_card_play, _) => GameEvent::PlaySpell(as_card_play),
             PlayerAction::ActivateAbility(as_ability, _) => GameEvent::ActivateAbility(as_ability),
             PlayerAction::Pass => {
@@ -583,8 +1881,169 @@ impl Game {
         */
     }
 
+    /// Asks the player which creatures attack and what each of them
+    /// attacks, one at a time, until they pass. Their teammates' creatures
+    /// attack along with theirs.
+    fn choose_attackers(&mut self, player: PlayerID) -> Vec<(PermanentID, AttackTarget)> {
+        let team = self.teammates(player);
+        let defenders: Vec<_> = self.remaining_players()
+            .into_iter()
+            .filter(|other| !team.contains(other) && self.in_range(player, *other))
+            .collect();
+        let mut targets: Vec<_> = defenders.iter().map(|defender| AttackTarget::Player(*defender)).collect();
+        targets.extend(self.battlefield
+            .values()
+            .filter(|perm| perm.type_line.is(CardType::Planeswalker) && defenders.contains(&perm.owner))
+            .map(|perm| AttackTarget::Planeswalker(perm.id)));
+
+        let mut attackers: Vec<_> = self.battlefield
+            .values()
+            .filter(|perm| team.contains(&perm.owner))
+            .map(|perm| perm.id)
+            .collect();
+
+        let mut chosen = vec![];
+        loop {
+            let mut options = vec![PlayerAction::Pass];
+            for attacker in &attackers {
+                for target in targets.iter().filter(|target| self.can_attack(*attacker, **target)) {
+                    let description = format!("{} attacks {}", self.get(*attacker).name, self.describe_attack_target(target));
+                    options.push(PlayerAction::Attack(*attacker, *target, description));
+                }
+            }
+            if options.len() == 1 { break }
+
+            info!("Asking {:?} to declare attackers", player);
+            match self.interface.choose_options(player, options) {
+                PlayerAction::Attack(attacker, target, _) if attackers.contains(&attacker) && targets.contains(&target) => {
+                    attackers.retain(|other| *other != attacker);
+                    chosen.push((attacker, target));
+                }
+                _ => break,
+            }
+        }
+        chosen
+    }
+
+    /// Asks the defending player which of their creatures block which
+    /// attackers, one at a time, until they pass.
+    fn choose_blockers(&mut self, player: PlayerID) -> Vec<(PermanentID, PermanentID)> {
+        let attackers: Vec<_> = self.combat.attacks
+            .iter()
+            .filter(|attack| attack.defending_player == player && self.battlefield.contains_key(&attack.attacker))
+            .map(|attack| attack.attacker)
+            .collect();
+
+        let mut blockers: Vec<_> = self.battlefield
+            .values()
+            .filter(|perm| perm.owner == player && !self.combat.is_blocking(perm.id))
+            .map(|perm| perm.id)
+            .collect();
+
+        let mut chosen = vec![];
+        loop {
+            let mut options = vec![PlayerAction::Pass];
+            for blocker in &blockers {
+                for attacker in attackers.iter().filter(|attacker| self.can_block(*blocker, **attacker)) {
+                    let description = format!("{} blocks {}", self.get(*blocker).name, self.get(*attacker).name);
+                    options.push(PlayerAction::Block(*blocker, *attacker, description));
+                }
+            }
+            if options.len() == 1 { break }
+
+            info!("Asking {:?} to declare blockers", player);
+            match self.interface.choose_options(player, options) {
+                PlayerAction::Block(blocker, attacker, _) if blockers.contains(&blocker) && attackers.contains(&attacker) => {
+                    blockers.retain(|other| *other != blocker);
+                    chosen.push((blocker, attacker));
+                }
+                _ => break,
+            }
+        }
+        chosen
+    }
+
+    fn can_attack(&self, attacker: PermanentID, target: AttackTarget) -> bool {
+        self.query(CanAttackQuery::new(attacker, target), &AbilityOrdering::build_from(self)).can_attack
+    }
+
+    fn can_block(&self, blocker: PermanentID, attacker: PermanentID) -> bool {
+        self.query(CanBlockQuery::new(blocker, attacker), &AbilityOrdering::build_from(self)).can_block
+    }
+
+    fn describe_attack_target(&self, target: &AttackTarget) -> String {
+        match target {
+            AttackTarget::Player(player) => format!("{:?}", player),
+            AttackTarget::Planeswalker(perm) => self.get(*perm).name.clone(),
+        }
+    }
+
+    // TODO first strike and double strike creatures deal damage in a step
+    // of their own.
+    /// Each attacking creature deals damage to what it attacks, or to the
+    /// creatures blocking it, and each blocking creature to what it blocks.
+    fn combat_damage(&self) -> Vec<GameEvent> {
+        let order = AbilityOrdering::build_from(self);
+        let power = |perm: PermanentID| self.observe_perm(perm, &order)
+            .power_toughness
+            .map_or(0, |pt| pt.power.max(0) as u32);
+        let deal = |to, amount, from| GameEvent::DealDamage(to, amount, EventSource::Combat(from));
+
+        let mut events = vec![];
+        for attack in &self.combat.attacks {
+            if !self.battlefield.contains_key(&attack.attacker) { continue }
+            let mut remaining = power(attack.attacker);
+
+            if attack.blockers.is_empty() {
+                let target_remains = match attack.target {
+                    AttackTarget::Player(player) => !self.get(player).eliminated,
+                    AttackTarget::Planeswalker(perm) => self.battlefield.contains_key(&perm),
+                };
+                if target_remains {
+                    events.push(deal(attack.target.into(), remaining, attack.attacker));
+                }
+                continue
+            }
+
+            // A blocked creature stays blocked, even if its blockers are
+            // gone. Each blocker but the last gets lethal damage at most.
+            let blockers: Vec<_> = attack.blockers
+                .iter()
+                .filter(|blocker| self.battlefield.contains_key(blocker))
+                .collect();
+            for (i, blocker) in blockers.iter().enumerate() {
+                let amount = if i + 1 == blockers.len() {
+                    remaining
+                } else {
+                    remaining.min(self.lethal_damage(attack.attacker, **blocker, &order))
+                };
+                remaining -= amount;
+                events.push(deal(GameObjectID::Permanent(**blocker), amount, attack.attacker));
+                events.push(deal(GameObjectID::Permanent(attack.attacker), power(**blocker), **blocker));
+            }
+        }
+
+        events.retain(|event| !matches!(event, GameEvent::DealDamage(_, 0, _)));
+        events
+    }
+
+    /// How much damage the attacker has to assign to the blocker to destroy it.
+    fn lethal_damage(&self, attacker: PermanentID, blocker: PermanentID, order: &AbilityOrdering) -> u32 {
+        if self.get(attacker).has_keyword(Keyword::Deathtouch) {
+            return 1
+        }
+        let blocker = self.observe_perm(blocker, order);
+        blocker.power_toughness
+            .as_ref()
+            .map_or(0, |pt| (pt.toughness - blocker.damage).max(0) as u32)
+    }
+
+    fn is_land_play(&self, as_card_play: AssignedCardPlay) -> bool {
+        self.spell_attrs(as_card_play).type_line.is(CardType::Land)
+    }
+
     fn can_play_sorceries(&self, player: PlayerID) -> bool {
-        player == self.active_player 
+        self.is_active(player)
         && self.turn_step.is_main_phase() 
         && self.game_stack.is_empty()
     }
@@ -592,60 +2051,370 @@ impl Game {
     fn add_ability(&mut self, ability: LatentAbility) -> AbilityID {
         let id = self.ability_ids.get_id();
         let ability = Ability::new(ability, id);
-        self.abilities.insert(id, ability);
+        Arc::make_mut(&mut self.abilities).insert(id, ability);
         id
     }
 
     fn add_card_play(&mut self, card_play: CardPlay) -> CardPlayID {
         let id = self.card_play_ids.get_id();
-        self.card_plays.insert(id, card_play);
+        Arc::make_mut(&mut self.card_plays).insert(id, card_play);
         id
     }
 
-    pub fn run(&mut self) {
+    /// Plays the game until only one player remains, or until everyone
+    /// left loses at the same time.
+    pub fn run(&mut self) -> GameResult {
         info!("gameloop: Starting game loop");
         self.push_event(GameEvent::StartTurn(self.active_player));
+        // Everyone draws their opening hand first, in APNAP order
+        let opening_hands: Vec<_> = self.apnap_order().into_iter().map(GameEvent::OpeningHand).collect();
+        self.push_events(opening_hands.into_iter().rev());
+        self.resume()
+    }
+
+    /// Plays the game on from where it is, like after it was loaded.
+    pub fn resume(&mut self) -> GameResult {
         let mut ability_order =  AbilityOrdering::build_from(self);
 
         loop {
-            let event = self.event_stack.pop().unwrap();
-            info!("gameloop: {} events queued and Current event {:?}", self.event_stack.len(), event);
+            let queued = self.event_stack.pop().unwrap();
+            self.handle_event(queued, &ability_order);
+
+            // Losses queued together happen simultaneously, so only check
+            // once the last of them has been applied.
+            let more_losses = matches!(self.event_stack.last(), Some(QueuedEvent { event: GameEvent::Lose(..), .. }));
+            if !more_losses && self.check_game_over() {
+                info!("gameloop: Game over {:?}", self.result);
+                return std::mem::take(&mut self.result);
+            }
 
-            let event = match ability_order.listen(event, self) {
-                ListenResult::Replaced(new_evs) => {
-                    info!("Event Replaced with {} event(s)", new_evs.len());
-                    self.push_events(new_evs);
-                    continue;
-                }
+            // The game state has changed, so the ability order should be updated.
+            ability_order = AbilityOrdering::build_from(self);
+        }
+    }
+
+    /// Handles every queued event, without checking if the game is over.
+    #[cfg(test)]
+    pub(crate) fn handle_events(&mut self) {
+        while let Some(queued) = self.event_stack.pop() {
+            self.handle_event(queued, &AbilityOrdering::build_from(self));
+        }
+    }
+
+    /// Replaces the event, lets abilities listen to it and then applies
+    /// it, unless it was replaced.
+    fn handle_event(&mut self, queued: QueuedEvent, ability_order: &AbilityOrdering) {
+        let QueuedEvent { event, mut applied_replacements } = queued;
+        info!("gameloop: {} events queued and Current event {:?}", self.event_stack.len(), event);
+
+        if let GameEvent::Simultaneously(events) = event {
+            self.apply_simultaneously(events, ability_order);
+            return
+        }
+
+        let replacements = ability_order.replacements(&event, &applied_replacements, self);
+        if !replacements.is_empty() {
+            let (as_ability, new_evs) = self.pick_replacement(&event, replacements);
+            applied_replacements.push(as_ability);
+            self.push_replaced_events(new_evs, applied_replacements);
+            return
+        }
+
+        let event = match ability_order.listen(event, self) {
+            ListenResult::Replaced(new_evs) => {
+                info!("Event Replaced with {} event(s)", new_evs.len());
+                self.push_events(new_evs);
+                return
+            }
+            ListenResult::Triggered(ev, new_evs) => {
+                info!("Event triggered {} event(s)", new_evs.len());
+                self.push_events(new_evs);
+                ev
+            }
+            ListenResult::Ignored(ev) => ev
+        };
+
+        let triggered = self.collect_stack_triggers(&event, ability_order);
+
+        // The event wasn't canceled, so we are now applying it.
+        self.default_event_handler(event);
+        self.put_triggers_on_stack(triggered);
+    }
+
+    /// Applies the events all at once. Every event is replaced first, and
+    /// what they trigger is decided by looking at the game before any of 
+    /// them have happened.
+    fn apply_simultaneously(&mut self, mut events: Vec<GameEvent>, order: &AbilityOrdering) {
+        info!("Applying {} events simultaneously", events.len());
+        // Players choose how to replace the events affecting them in APNAP
+        // order. The last pending event is looked at first.
+        events.sort_by_key(|event| std::cmp::Reverse(self.apnap_position(event.affected_player(self))));
+        let mut pending: Vec<_> = events.into_iter().map(QueuedEvent::new).collect();
+        let mut replaced = vec![];
+
+        while let Some(QueuedEvent { event, mut applied_replacements }) = pending.pop() {
+            let replacements = order.replacements(&event, &applied_replacements, self);
+            if replacements.is_empty() {
+                replaced.push(event);
+                continue
+            }
+
+            let (as_ability, new_evs) = self.pick_replacement(&event, replacements);
+            applied_replacements.push(as_ability);
+            pending.extend(new_evs.into_iter().map(|event| QueuedEvent {
+                event,
+                applied_replacements: applied_replacements.clone(),
+            }));
+        }
+
+        let mut triggered_events = vec![];
+        let mut triggered_objects = vec![];
+        let mut to_apply = vec![];
+        for event in replaced {
+            triggered_objects.extend(self.collect_stack_triggers(&event, order));
+            match order.listen(event, self) {
+                ListenResult::Replaced(new_evs) => triggered_events.extend(new_evs),
                 ListenResult::Triggered(ev, new_evs) => {
-                    info!("Event triggered {} event(s)", new_evs.len());
-                    self.push_events(new_evs);
-                    ev
+                    triggered_events.extend(new_evs);
+                    to_apply.push(ev);
                 }
-                ListenResult::Ignored(ev) => ev
-            };
+                ListenResult::Ignored(ev) => to_apply.push(ev),
+            }
+        }
 
-            // The event wasn't canceled, so we are now applying it.
+        for event in to_apply {
             self.default_event_handler(event);
-            // The game state has changed, so the ability order should be updated.
-            ability_order = AbilityOrdering::build_from(self);
         }
+        self.push_events(triggered_events);
+        self.put_triggers_on_stack(triggered_objects);
     }
 
-    pub fn next_player(&self, before: PlayerID) -> PlayerID {
-        let idx = self.players.iter()
+    /// Picks which of the replacement effects to apply to the event, asking
+    /// the affected player if there is more than one.
+    fn pick_replacement(&mut self, event: &GameEvent, mut replacements: Vec<(AssignedAbility, Vec<GameEvent>)>) -> (AssignedAbility, Vec<GameEvent>) {
+        let chosen = match event.affected_player(self) {
+            Some(player) if replacements.len() > 1 => self.choose_replacement(player, &replacements),
+            _ => 0,
+        };
+        let (as_ability, new_evs) = replacements.swap_remove(chosen);
+        info!("Event Replaced by {:?} with {} event(s)", as_ability, new_evs.len());
+        (as_ability, new_evs)
+    }
+
+    // TODO triggered abilities should be put on the stack the next time
+    // a player would receive priority, and each player should choose the
+    // order of their own.
+    /// Triggered abilities go on the stack in APNAP order, so the active
+    /// player's resolve last.
+    fn put_triggers_on_stack(&mut self, mut triggered: Vec<Object>) {
+        triggered.sort_by_key(|object| self.apnap_position(Some(object.controller)));
+        for object in triggered {
+            info!("Triggered ability put on the stack");
+            self.game_stack.push(object);
+        }
+    }
+
+    fn next_timestamp(&mut self) -> usize {
+        self.timestamp += 1;
+        self.timestamp
+    }
+
+    /// Asks the affected player which replacement effect to apply first.
+    /// Returns the index of the chosen replacement.
+    fn choose_replacement(&mut self, player: PlayerID, replacements: &[(AssignedAbility, Vec<GameEvent>)]) -> usize {
+        let options = replacements
+            .iter()
+            .map(|(as_ability, _)| PlayerAction::Replacement(
+                *as_ability, 
+                self.get(as_ability.ability).base.description.clone()))
+            .collect();
+
+        info!("Asking {:?} which replacement effect to apply", player);
+        match self.interface.choose_options(player, options) {
+            PlayerAction::Replacement(chosen, _) => replacements
+                .iter()
+                .position(|(as_ability, _)| *as_ability == chosen)
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    pub fn remaining_players(&self) -> Vec<PlayerID> {
+        self.players
+            .iter()
+            .filter(|player| !player.eliminated)
+            .map(|player| player.id)
+            .collect()
+    }
+
+    /// The players still in the game, starting with the active player
+    /// and going around in turn order.
+    pub fn apnap_order(&self) -> Vec<PlayerID> {
+        let idx = self.seat(self.active_player);
+        (0..self.players.len())
+            .map(|offset| &self.players[(idx + offset) % self.players.len()])
+            .filter(|player| !player.eliminated)
+            .map(|player| player.id)
+            .collect()
+    }
+
+    /// Where the player comes in APNAP order. Anything not belonging to
+    /// a player remaining in the game comes first.
+    fn apnap_position(&self, player: Option<PlayerID>) -> usize {
+        player
+            .and_then(|player| self.apnap_order().iter().position(|other| *other == player))
+            .map_or(0, |position| position + 1)
+    }
+
+    fn seat(&self, player: PlayerID) -> usize {
+        self.players.iter()
+            .position(|other| other.id == player)
+            .expect("Player should be in the game")
+    }
+
+    /// Whether the other player is within the player's range of influence.
+    /// Players who left the game don't count towards the distance.
+    pub fn in_range(&self, player: PlayerID, other: PlayerID) -> bool {
+        let Some(range) = self.config.range_of_influence else { return true };
+        let remaining = self.remaining_players();
+        let seat = |player| remaining.iter().position(|other| *other == player);
+        let (Some(a), Some(b)) = (seat(player), seat(other)) else { return false };
+
+        let distance = a.abs_diff(b);
+        distance.min(remaining.len() - distance) <= range
+    }
+
+    /// Whether the target is controlled by a player within range.
+    fn can_reach(&self, player: PlayerID, target: &Target) -> bool {
+        let controller = match target {
+            Target::Permanent(perm) => self.battlefield.get(perm).map(|perm| perm.owner),
+            Target::Player(other) => Some(*other),
+            Target::Object(object) => self.game_stack
+                .iter()
+                .find(|stack_object| stack_object.id == *object)
+                .map(|stack_object| stack_object.controller),
+        };
+        controller.is_some_and(|controller| self.in_range(player, controller))
+    }
+
+    /// Ends the game if at most one player remains. Otherwise, players
+    /// that just lost leave the game, taking everything they own with them.
+    fn check_game_over(&mut self) -> bool {
+        if self.leaving_players.is_empty() {
+            return false
+        }
+
+        let remaining = self.remaining_players();
+        match remaining[..] {
+            [] => {
+                // Everyone left lost at the same time, so the game is a draw
+                // between them.
+                let leaving = std::mem::take(&mut self.leaving_players);
+                let (draws, losers) = std::mem::take(&mut self.result.losers)
+                    .into_iter()
+                    .partition(|(player, _)| leaving.contains(player));
+                self.result.draws = draws;
+                self.result.losers = losers;
+                true
+            }
+            // The last team standing wins together
+            [first, ..] if self.teammates(first).len() == remaining.len() => {
+                for winner in remaining {
+                    if !self.result.is_winner(winner) {
+                        self.result.winners.push((winner, GameRule::LastPlayerStanding.into()));
+                    }
+                }
+                true
+            }
+            _ => {
+                for player in std::mem::take(&mut self.leaving_players) {
+                    self.remove_player(player);
+                }
+                false
+            }
+        }
+    }
+
+    /// Removes everything the player owns or controls from the game.
+    fn remove_player(&mut self, player: PlayerID) {
+        info!("Player {:?} is leaving the game", player);
+        // Whatever was about to happen to the player doesn't anymore. If it
+        // was their turn, the rest of its steps are skipped too, but what
+        // is still pending for everyone else happens before the next turn.
+        // Priority they would get passes on to the next player.
+        let is_active = player == self.active_player;
+        let events = std::mem::take(&mut self.event_stack);
+        self.event_stack = events
+            .into_iter()
+            .filter(|queued| match queued.event {
+                GameEvent::GivePriority(_) => true,
+                GameEvent::NextStep => !is_active,
+                ref event => event.affected_player(self) != Some(player),
+            })
+            .collect();
+        if is_active {
+            let next_turn = QueuedEvent::new(GameEvent::StartTurn(self.next_player(player)));
+            self.event_stack.insert(0, next_turn);
+        }
+
+        // Their auras and equipment come off what they were attached to,
+        // and anything attached to their permanents falls off. Auras left
+        // unattached go to the graveyard with state based actions.
+        let leaving: Vec<_> = self.battlefield
+            .values()
+            .filter(|perm| perm.owner == player)
+            .map(|perm| (perm.id, perm.attachments.clone()))
+            .collect();
+        for (perm, attachments) in leaving {
+            self.unattach(perm);
+            for attachment in attachments {
+                self.unattach(attachment);
+            }
+        }
+
+        self.battlefield.retain(|_, perm| perm.owner != player);
+        self.tokens_elsewhere.retain(|token| token.owner != player);
+        self.game_stack.retain(|object| object.controller != player);
+        self.cards.remove_player(player);
+        self.get_mut(player).mana_pool.clear();
+        // Creatures attacking the player are removed from combat
+        self.combat.attacks.retain(|attack| attack.defending_player != player);
+    }
+
+    /// The players still in the game on the player's team, in turn order.
+    /// Without teams, that is only the player.
+    pub fn teammates(&self, player: PlayerID) -> Vec<PlayerID> {
+        let team = self.seat(player) / self.config.team_size;
+        self.players
+            .iter()
             .enumerate()
-            .find(|player| player.1.id == before)
-            .unwrap().0;
-        let new_idx = (idx + 1) % self.players.len();
+            .filter(|(seat, other)| seat / self.config.team_size == team && !other.eliminated)
+            .map(|(_, other)| other.id)
+            .collect()
+    }
+
+    /// Whether it is the player's turn, or their team's.
+    pub fn is_active(&self, player: PlayerID) -> bool {
+        self.teammates(self.active_player).contains(&player)
+    }
 
-        self.players[new_idx].id
+    /// The next player in turn order who is still in the game and isn't
+    /// on the same team.
+    pub fn next_player(&self, before: PlayerID) -> PlayerID {
+        let idx = self.seat(before);
+        let team = self.teammates(before);
+
+        (1..=self.players.len())
+            .map(|offset| &self.players[(idx + offset) % self.players.len()])
+            .find(|player| !player.eliminated && !team.contains(&player.id))
+            .unwrap_or(&self.players[idx])
+            .id
     }
 }
 
 impl IDMapper<Ability> for Game {
     fn get(&self, id: ID<Ability>) -> &Ability { self.abilities.get(&id).unwrap() }
-    fn get_mut(&mut self, id: ID<Ability>) -> &mut Ability { self.abilities.get_mut(&id).unwrap() }
+    fn get_mut(&mut self, id: ID<Ability>) -> &mut Ability { Arc::make_mut(&mut self.abilities).get_mut(&id).unwrap() }
 }
 
 impl IDMapper<Player> for Game {
@@ -654,7 +2423,7 @@ impl IDMapper<Player> for Game {
 }
 impl IDMapper<CardPlay> for Game {
     fn get(&self, id: ID<CardPlay>) -> &CardPlay { self.card_plays.get(&id).unwrap() }
-    fn get_mut(&mut self, id: ID<CardPlay>) -> &mut CardPlay { self.card_plays.get_mut(&id).unwrap() }
+    fn get_mut(&mut self, id: ID<CardPlay>) -> &mut CardPlay { Arc::make_mut(&mut self.card_plays).get_mut(&id).unwrap() }
 }
 
 impl IDMapper<Card> for Game {
@@ -671,3 +2440,139 @@ impl IDMapper<Permanent> for Game {
     fn get(&self, id: ID<Permanent>) -> &Permanent { self.battlefield.get(&id).unwrap() }
     fn get_mut(&mut self, id: ID<Permanent>) -> &mut Permanent { self.battlefield.get_mut(&id).unwrap() }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{client::FirstOption, impls::cards::get_card};
+
+    use super::*;
+
+    fn deck() -> Vec<LatentCard> {
+        ["mountain", "mountain", "goblin assailant", "goblin assailant"]
+            .repeat(2)
+            .into_iter()
+            .map(get_card)
+            .collect()
+    }
+
+    /// Puts the card from the owner's library onto the battlefield.
+    fn enter(game: &mut Game, owner: PlayerID, definition: &str, attached_to: Option<PermanentID>) -> PermanentID {
+        let card = game.cards
+            .get_cards(Zone::Deck(owner))
+            .into_iter()
+            .find(|card| card.definition == definition)
+            .unwrap()
+            .clone();
+        let mut perm = Permanent::from_card(&card, game.perm_ids.get_id(), owner);
+        perm.attached_to = attached_to;
+        let id = perm.id;
+        game.push_event(GameEvent::RegisterPermanent(perm));
+        game.handle_events();
+        id
+    }
+
+    #[test]
+    fn leaving_players_take_their_attachments_with_them() {
+        let yours = vec![get_card("goblin assailant"), get_card("bonesplitter")];
+        let theirs = vec![get_card("goblin assailant"), get_card("holy strength")];
+        let mut game = Game::new(vec![yours, theirs, deck()], GameConfig::constructed());
+        let (you, leaving) = (game.players[0].id, game.players[1].id);
+
+        let your_goblin = enter(&mut game, you, "goblin assailant", None);
+        let their_goblin = enter(&mut game, leaving, "goblin assailant", None);
+        let bonesplitter = enter(&mut game, you, "bonesplitter", Some(their_goblin));
+        enter(&mut game, leaving, "holy strength", Some(your_goblin));
+
+        game.remove_player(leaving);
+        assert_eq!(game.get(bonesplitter).attached_to, None);
+        assert!(game.get(your_goblin).attachments.is_empty());
+        assert!(game.cards.get_cards(Zone::Deck(leaving)).is_empty());
+        assert!(game.cards.hand(leaving).is_empty());
+    }
+
+    #[test]
+    fn attaching_to_a_permanent_that_left_does_nothing() {
+        let mut game = Game::new(vec![vec![get_card("holy strength"), get_card("bonesplitter")], deck()], GameConfig::constructed());
+        let you = game.players[0].id;
+        let gone = game.perm_ids.get_id();
+
+        let aura = enter(&mut game, you, "holy strength", Some(gone));
+        assert_eq!(game.get(aura).attached_to, None);
+
+        let equipment = enter(&mut game, you, "bonesplitter", None);
+        game.push_event(GameEvent::Attach(equipment, gone, EventSource::Player(you)));
+        game.handle_events();
+        assert_eq!(game.get(equipment).attached_to, None);
+    }
+
+    #[test]
+    fn tokens_that_left_the_battlefield_cease_to_exist() {
+        let mut game = Game::new(vec![deck(), deck()], GameConfig::constructed());
+        let you = game.players[0].id;
+        let token = Permanent::token("Knight".into(), TypeLine::empty().add(CardType::Creature), None, game.perm_ids.get_id(), you);
+        let token_id = token.id;
+        game.push_event(GameEvent::RegisterPermanent(token));
+        game.handle_events();
+        game.push_event(GameEvent::Sacrifice(token_id, EventSource::Player(you)));
+        game.handle_events();
+        assert_eq!(game.tokens_elsewhere.len(), 1);
+
+        let actions = check_state_based_actions(&GameFacade::new(&game));
+        assert!(matches!(actions[..], [GameEvent::CeaseToExist(id, _)] if id == token_id));
+        game.push_event(GameEvent::Simultaneously(actions));
+        game.handle_events();
+        assert!(game.tokens_elsewhere.is_empty());
+    }
+
+    #[test]
+    fn restoring_puts_the_game_back_and_gives_priority_again() {
+        let mut game = Game::new(vec![deck(), deck()], GameConfig::constructed());
+        let you = game.players[0].id;
+        let point = game.restore_point(you);
+
+        game.default_event_handler(GameEvent::DealDamage(GameObjectID::Player(you), 5, EventSource::Player(you)));
+        game.default_event_handler(GameEvent::DrawCard(you));
+        game.restore(point);
+        assert_eq!(game.get(you).life_total, 20);
+        assert!(game.cards.hand(you).is_empty());
+        assert!(matches!(game.event_stack[..], [QueuedEvent { event: GameEvent::GivePriority(player), .. }] if player == you));
+    }
+
+    #[test]
+    fn scried_cards_are_still_known() {
+        let mut game = Game::new(vec![deck(), deck()], GameConfig::constructed()).with_interface(Box::new(FirstOption));
+        let (player, opponent) = (game.players[0].id, game.players[1].id);
+
+        game.push_event(GameEvent::Scry(player, 2));
+        game.handle_events();
+        for card in game.cards.library_top(player, 2) {
+            assert!(game.cards.can_see(player, card));
+            assert!(!game.cards.can_see(opponent, card));
+        }
+        assert!(!game.cards.can_see(player, game.cards.library_top(player, 3)[2]));
+    }
+
+    #[test]
+    fn two_headed_giant_teams_share_their_life_total() {
+        let mut game = Game::new(vec![deck(), deck(), deck(), deck()], GameConfig::two_headed_giant());
+        let ids: Vec<_> = game.players.iter().map(|player| player.id).collect();
+
+        game.default_event_handler(GameEvent::DealDamage(GameObjectID::Player(ids[0]), 3, EventSource::Player(ids[2])));
+        let life: Vec<_> = game.players.iter().map(|player| player.life_total).collect();
+        assert_eq!(life, vec![27, 27, 30, 30]);
+    }
+
+    #[test]
+    fn two_headed_giant_teams_take_turns_and_win_together() {
+        let game = Game::new(vec![deck(), deck(), deck(), deck()], GameConfig::two_headed_giant());
+        let ids: Vec<_> = game.players.iter().map(|player| player.id).collect();
+        assert_eq!(game.next_player(ids[0]), ids[2]);
+        assert_eq!(game.next_player(ids[2]), ids[0]);
+
+        // The team going first skips its first draw, so the other team
+        // runs out of cards first
+        let result = game.with_interface(Box::new(FirstOption)).run();
+        assert!(result.is_winner(ids[0]) && result.is_winner(ids[1]));
+        assert!(result.is_loser(ids[2]) && result.is_loser(ids[3]));
+    }
+}
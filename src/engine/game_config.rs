@@ -0,0 +1,130 @@
+use serde::{Serialize, Deserialize};
+
+use super::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Format {
+    Constructed,
+    Commander,
+    TwoHeadedGiant,
+    Custom,
+}
+
+/// How players replace an opening hand they don't want to keep.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum MulliganType {
+    /// Draw a full new hand, then put a card on the bottom of the library
+    /// for each mulligan taken.
+    London,
+    /// Draw a new hand with one card fewer each time.
+    Paris,
+    /// Like Paris, but scry 1 after keeping a smaller hand.
+    Vancouver,
+}
+
+/// The rules a game is played with.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameConfig {
+    pub format: Format,
+    pub starting_life: i32,
+    pub starting_hand_size: usize,
+    /// Players discard down to this many cards at the end of their turn.
+    pub max_hand_size: Option<usize>,
+    pub mulligan: MulliganType,
+    /// Whether the player going first draws a card on their first turn.
+    pub first_player_draws: bool,
+    pub turn_structure: Vec<TurnStep>,
+    /// How many seats away a player's spells, abilities and attacks can
+    /// reach. Everyone can reach everyone if this is unset.
+    pub range_of_influence: Option<usize>,
+    /// Players sitting next to each other form teams of this size. A team
+    /// shares its life total and takes its turns together.
+    pub team_size: usize,
+}
+
+impl GameConfig {
+    pub fn constructed() -> Self {
+        Self {
+            format: Format::Constructed,
+            starting_life: 20,
+            starting_hand_size: 7,
+            max_hand_size: Some(7),
+            mulligan: MulliganType::London,
+            first_player_draws: false,
+            turn_structure: DEFAULT_TURN_STRUCTURE.to_vec(),
+            range_of_influence: None,
+            team_size: 1,
+        }
+    }
+
+    /// Commander is usually played with more than two players, so nobody
+    /// skips their first draw.
+    pub fn commander() -> Self {
+        Self {
+            format: Format::Commander,
+            starting_life: COMMANDER_STARTING_LIFE,
+            first_player_draws: true,
+            ..Self::constructed()
+        }
+    }
+
+    /// Two teams of two, each starting at 30 life. The team going first
+    /// skips its first draw.
+    pub fn two_headed_giant() -> Self {
+        Self {
+            format: Format::TwoHeadedGiant,
+            starting_life: 30,
+            team_size: 2,
+            ..Self::constructed()
+        }
+    }
+
+    /// Starts from the constructed rules, to be changed with the `with_`
+    /// methods.
+    pub fn custom() -> Self {
+        Self {
+            format: Format::Custom,
+            ..Self::constructed()
+        }
+    }
+
+    pub fn with_starting_life(mut self, life: i32) -> Self {
+        self.starting_life = life;
+        self
+    }
+
+    pub fn with_starting_hand_size(mut self, hand_size: usize) -> Self {
+        self.starting_hand_size = hand_size;
+        self
+    }
+
+    pub fn with_max_hand_size(mut self, max_hand_size: Option<usize>) -> Self {
+        self.max_hand_size = max_hand_size;
+        self
+    }
+
+    pub fn with_mulligan(mut self, mulligan: MulliganType) -> Self {
+        self.mulligan = mulligan;
+        self
+    }
+
+    pub fn with_first_player_draws(mut self, draws: bool) -> Self {
+        self.first_player_draws = draws;
+        self
+    }
+
+    pub fn with_turn_structure(mut self, turn_structure: Vec<TurnStep>) -> Self {
+        self.turn_structure = turn_structure;
+        self
+    }
+
+    pub fn with_range_of_influence(mut self, range: usize) -> Self {
+        self.range_of_influence = Some(range);
+        self
+    }
+
+    pub fn with_team_size(mut self, team_size: usize) -> Self {
+        self.team_size = team_size.max(1);
+        self
+    }
+}
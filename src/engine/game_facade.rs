@@ -26,19 +26,55 @@ impl<'a> GameFacade<'a> {
 
 
     pub fn players(&'a self) -> impl 'a + Iterator<Item=PlayerID> {
-        Box::new(self.game.players.iter().map(|player| player.id))
+        Box::new(self.game.remaining_players().into_iter())
     }
 
     pub fn player_life(&self, player: PlayerID) -> i32 {
-        // TODO maybe should be query
-        self.game.get(player).life_total
+        self.observe_player(player).life_total
+    }
+
+    pub fn observe_player(&self, player: PlayerID) -> ObservePlayerQuery {
+        self.game.query(ObservePlayerQuery::new(player), &self.ability_order)
+    }
+
+    pub fn player_poison(&self, player: PlayerID) -> u32 {
+        self.game.get(player).poison_counters
+    }
+
+    /// The most combat damage the player has taken from a single commander.
+    pub fn commander_damage(&self, player: PlayerID) -> u32 {
+        self.game.commanders.most_damage_to(player)
+    }
+
+    /// Tokens that left the battlefield, but still exist.
+    pub fn tokens_elsewhere(&'a self) -> impl 'a + Iterator<Item=PermanentID> {
+        self.game.tokens_elsewhere.iter().map(|token| token.id)
+    }
+
+    pub fn drew_from_empty_library(&self, player: PlayerID) -> bool {
+        self.game.get(player).drew_from_empty_library
+    }
+
+    pub fn can_lose(&self, player: PlayerID) -> bool {
+        self.game.query(WinLoseQuery::new(player), &self.ability_order).can_lose
+    }
+
+    /// The highest chapter number among the permanent's abilities, if it has any.
+    pub fn final_chapter(&self, perm: PermanentID) -> Option<u32> {
+        self.game.perm_abilities(perm, &self.ability_order)
+            .into_iter()
+            .filter_map(|as_ability| self.game.get(as_ability.ability).base.class.chapter())
+            .max()
+    }
+
+    /// Whether any ability from the permanent is waiting on the stack.
+    pub fn has_ability_on_stack(&self, perm: PermanentID) -> bool {
+        self.game.game_stack.iter().any(|object| 
+            matches!(object.resolve, ObjectResolve::AbilityActivate(as_ability) if as_ability.perm == perm))
     }
 
     pub fn observe_perm(&self, perm: PermanentID) -> Permanent {
-        let query = self.game.query(
-            ObservePermQuery::new(self.game.get(perm).clone()),
-            &self.ability_order);
-        query.perm
+        self.game.observe_perm(perm, &self.ability_order)
     }
 }
 
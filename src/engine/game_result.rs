@@ -0,0 +1,26 @@
+use serde::{Serialize, Deserialize};
+
+use super::prelude::*;
+
+/// The outcome of a finished game. Each player ends up in exactly one
+/// of these lists, along with the reason they got there.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GameResult {
+    pub winners: Vec<(PlayerID, EventSource)>,
+    pub losers: Vec<(PlayerID, EventSource)>,
+    pub draws: Vec<(PlayerID, EventSource)>,
+}
+
+impl GameResult {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_winner(&self, player: PlayerID) -> bool {
+        self.winners.iter().any(|(id, _)| *id == player)
+    }
+
+    pub fn is_loser(&self, player: PlayerID) -> bool {
+        self.losers.iter().any(|(id, _)| *id == player)
+    }
+}
@@ -1,28 +1,49 @@
 use super::prelude::*;
 
+/// Everything one player is allowed to know about the game. Cards in
+/// hidden zones are only included if the player can see them.
+#[derive(Clone)]
 pub struct GameStateSnapshot {
+    pub player: PlayerID,
     pub opponent_perms: Vec<PermanentSnapshot>,
     pub perms: Vec<PermanentSnapshot>,
     pub hand: Vec<CardSnapshot>,
     pub graveyard: Vec<CardSnapshot>,
+    /// Graveyards are public, so every opponent's is included.
+    pub opponent_graveyards: Vec<(PlayerID, Vec<CardSnapshot>)>,
+    /// Exiled cards, and cards in other hidden zones that the player can
+    /// see, like opponents' revealed cards or the top of their own library.
+    pub revealed: Vec<CardSnapshot>,
+    /// Every player's command zone.
+    pub command: Vec<CardSnapshot>,
+    /// How many cards each opponent has in hand.
+    pub opponent_hand_sizes: Vec<(PlayerID, usize)>,
 }
 
+#[derive(Clone)]
 pub struct PermanentSnapshot {
-    pub permanent: Attributes,
+    /// The characteristics of the face that is up.
+    pub permanent: FaceSnapshot,
+    pub face: usize,
     pub state: State,
     pub id: PermanentID,
 }
 
+#[derive(Clone)]
 pub struct CardSnapshot {
-    pub card: Attributes,
+    pub card: FaceSnapshot,
+    /// Every face of the card other than the front one.
+    pub other_faces: Vec<FaceSnapshot>,
     pub id: CardID,
 }
 
+#[derive(Clone)]
 pub struct State {
     pub is_tapped: bool,
 }
 
-pub struct Attributes {
+#[derive(Clone)]
+pub struct FaceSnapshot {
     pub name: String,
     pub type_line: TypeLine,
     pub cost: Option<ManaCost>,
@@ -30,3 +51,115 @@ pub struct Attributes {
     pub flavor: String,
     pub power_toughness: Option<(i32, i32)>,
 }
+
+impl GameStateSnapshot {
+    pub fn for_player(game: &Game, player: PlayerID) -> Self {
+        // Players see power and toughness with every effect applied
+        let order = AbilityOrdering::build_from(game);
+        let (perms, opponent_perms) = game.battlefield
+            .keys()
+            .map(|perm| PermanentSnapshot::new(&game.observe_perm(*perm, &order), game))
+            .partition(|perm| game.get(perm.id).owner == player);
+
+        let snapshot_zone = |zone| game.cards
+            .get_cards(zone)
+            .into_iter()
+            .map(|card| CardSnapshot::new(card, game))
+            .collect();
+
+        let opponents: Vec<_> = game.remaining_players()
+            .into_iter()
+            .filter(|opponent| *opponent != player)
+            .collect();
+
+        let revealed = opponents
+            .iter()
+            .flat_map(|opponent| [Zone::Hand(*opponent), Zone::Deck(*opponent)])
+            .chain([Zone::Deck(player), Zone::Exile])
+            .flat_map(|zone| game.cards.get_cards(zone))
+            .filter(|card| game.cards.can_see(player, card.id))
+            .map(|card| CardSnapshot::new(card, game))
+            .collect();
+
+        let command = game.remaining_players()
+            .into_iter()
+            .flat_map(|player| game.cards.get_cards(Zone::Command(player)))
+            .map(|card| CardSnapshot::new(card, game))
+            .collect();
+
+        let opponent_graveyards = opponents
+            .iter()
+            .map(|opponent| (*opponent, snapshot_zone(Zone::Graveyard(*opponent))))
+            .collect();
+
+        let opponent_hand_sizes = opponents
+            .iter()
+            .map(|opponent| (*opponent, game.cards.hand(*opponent).len()))
+            .collect();
+
+        Self {
+            player,
+            opponent_perms,
+            perms,
+            hand: snapshot_zone(Zone::Hand(player)),
+            graveyard: snapshot_zone(Zone::Graveyard(player)),
+            opponent_graveyards,
+            revealed,
+            command,
+            opponent_hand_sizes,
+        }
+    }
+}
+
+impl PermanentSnapshot {
+    pub fn new(perm: &Permanent, game: &Game) -> Self {
+        let cost = perm.card.and_then(|card| game.get(card).face(perm.face).attrs.cost.clone());
+        Self {
+            permanent: FaceSnapshot {
+                name: perm.name.clone(),
+                type_line: perm.type_line.clone(),
+                cost,
+                abilities: describe_abilities(&perm.abilities, game),
+                flavor: perm.flavor.clone(),
+                power_toughness: perm.power_toughness.as_ref().map(|pt| (pt.power, pt.toughness)),
+            },
+            face: perm.face,
+            state: State {
+                is_tapped: perm.tapped,
+            },
+            id: perm.id,
+        }
+    }
+}
+
+impl CardSnapshot {
+    pub fn new(card: &Card, game: &Game) -> Self {
+        let mut faces = card.faces.iter().map(|face| FaceSnapshot::from_face(face, game));
+        Self {
+            card: faces.next().expect("Cards have at least one face"),
+            other_faces: faces.collect(),
+            id: card.id,
+        }
+    }
+}
+
+impl FaceSnapshot {
+    pub fn from_face(face: &Face, game: &Game) -> Self {
+        let attrs = &face.attrs;
+        Self {
+            name: attrs.name.clone(),
+            type_line: attrs.type_line.clone(),
+            cost: attrs.cost.clone(),
+            abilities: describe_abilities(&face.perm_abilities, game),
+            flavor: attrs.flavor.clone(),
+            power_toughness: attrs.power_toughness.as_ref().map(|pt| (pt.power, pt.toughness)),
+        }
+    }
+}
+
+fn describe_abilities(abilities: &[AbilityID], game: &Game) -> Vec<String> {
+    abilities
+        .iter()
+        .map(|ability| game.get(*ability).base.description.clone())
+        .collect()
+}
@@ -0,0 +1,10 @@
+use serde::{Serialize, Deserialize};
+
+/// Keyword abilities that the rules themselves give meaning to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Keyword {
+    Deathtouch,
+    FirstStrike,
+    Flying,
+    Vigilance,
+}
@@ -0,0 +1,72 @@
+use serde::{Serialize, Deserialize};
+
+use super::prelude::*;
+
+/// Which cards a search can find. A card has to have every listed type
+/// and supertype; an empty filter finds any card.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CardFilter {
+    pub card_types: Vec<CardType>,
+    pub card_super_types: Vec<CardSuperType>,
+}
+
+impl CardFilter {
+    pub fn any() -> Self {
+        Self {
+            card_types: vec![],
+            card_super_types: vec![],
+        }
+    }
+
+    pub fn with_type(mut self, card_type: CardType) -> Self {
+        self.card_types.push(card_type);
+        self
+    }
+
+    pub fn with_super_type(mut self, card_super_type: CardSuperType) -> Self {
+        self.card_super_types.push(card_super_type);
+        self
+    }
+
+    pub fn matches(&self, attrs: &Attributes) -> bool {
+        self.card_types.iter().all(|card_type| attrs.type_line.is(card_type.clone()))
+            && self.card_super_types.iter().all(|super_type| attrs.type_line.is(super_type.clone()))
+    }
+}
+
+/// Where a card found by searching a library is put.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum SearchDestination {
+    Hand,
+    Battlefield,
+    BattlefieldTapped,
+    /// On top of the library, after it is shuffled
+    LibraryTop,
+}
+
+/// "Search your library for a <filter> card, put it <destination>, then
+/// shuffle."
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LibrarySearch {
+    pub player: PlayerID,
+    pub filter: CardFilter,
+    pub destination: SearchDestination,
+    /// Whether the found card is revealed to every player
+    pub reveal: bool,
+}
+
+impl LibrarySearch {
+    pub fn new(player: PlayerID, filter: CardFilter, destination: SearchDestination) -> Self {
+        Self {
+            player,
+            filter,
+            destination,
+            reveal: false,
+        }
+    }
+
+    pub fn revealed(mut self) -> Self {
+        self.reveal = true;
+        self
+    }
+}
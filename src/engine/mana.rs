@@ -1,49 +1,114 @@
 use std::ops::{DerefMut, Deref};
 
+use serde::{Serialize, Deserialize};
 
-#[derive(Clone,PartialEq, Eq)]
+use super::prelude::*;
+
+
+#[derive(Clone,PartialEq, Eq, Debug)]
 pub struct AbilityCost {
     pub cost: Cost,
     pub tap: bool,
+    /// Loyalty counters added (positive) or removed (negative) to activate.
+    /// Only planeswalkers have loyalty abilities.
+    pub loyalty: Option<i32>,
+    pub speed: AbilitySpeed,
 }
 
 impl AbilityCost {
     pub fn empty() -> Self {
-        Self { cost: Cost::empty(), tap: false }
+        Self { cost: Cost::empty(), tap: false, loyalty: None, speed: AbilitySpeed::Instant }
+    }
+
+    pub fn with_mana(mut self, mana_cost: ManaCost) -> Self {
+        self.cost.mana_cost = mana_cost;
+        self
+    }
+
+    /// "Activate only as a sorcery"
+    pub fn with_sorcery_speed(mut self) -> Self {
+        self.speed = AbilitySpeed::Sorcery;
+        self
     }
 
     pub fn with_tap(mut self) -> Self {
         self.tap = true;
         self
     }
+
+    pub fn with_loyalty(mut self, loyalty: i32) -> Self {
+        self.loyalty = Some(loyalty);
+        self
+    }
+
+    pub fn is_loyalty(&self) -> bool {
+        self.loyalty.is_some()
+    }
+
+    pub fn is_sorcery_speed(&self) -> bool {
+        self.is_loyalty() || self.speed == AbilitySpeed::Sorcery
+    }
 }
 
-#[derive(Clone,PartialEq, Eq, Debug)]
+#[derive(Clone,PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct ManaCost {
     pub mana: Vec<ManaType>,
     pub generic_mana: usize,
+    /// How many {X} symbols the cost has. The player chooses a value for
+    /// X while casting, and each {X} becomes that much generic mana.
+    pub x: usize,
 }
 
 impl ManaCost {
     pub fn new(mana: Vec<ManaType>, generic_mana: usize) -> Self {
-        Self{ mana, generic_mana }
+        Self{ mana, generic_mana, x: 0 }
     }
 
     pub fn empty() -> Self {
         Self::new(vec![], 0)
     }
+
+    pub fn with_x(mut self, x: usize) -> Self {
+        self.x = x;
+        self
+    }
+
+    /// The cost with the chosen value in place of each {X}.
+    pub fn with_x_value(mut self, value: u32) -> Self {
+        self.generic_mana += self.x * value as usize;
+        self.x = 0;
+        self
+    }
+
+    /// "costs {N} more to cast"
+    pub fn increase_generic(&mut self, amount: usize) {
+        self.generic_mana += amount;
+    }
+
+    /// "costs {N} less to cast". Only generic mana can be reduced.
+    pub fn reduce_generic(&mut self, amount: usize) {
+        self.generic_mana = self.generic_mana.saturating_sub(amount);
+    }
+
+    /// The total amount of mana the cost needs, not counting {X}.
+    pub fn total(&self) -> usize {
+        self.mana.len() + self.generic_mana
+    }
 }
 
 
-#[derive(Clone,PartialEq, Eq)]
+#[derive(Clone,PartialEq, Eq, Debug)]
 pub struct Cost {
     pub mana_cost: ManaCost,
+    /// How many other cards from your graveyard have to be exiled, for escape.
+    pub exile_from_graveyard: usize,
 }
 
 impl Cost {
     pub fn empty() -> Self {
         Self {
             mana_cost: ManaCost::empty(),
+            exile_from_graveyard: 0,
         }
     }
 
@@ -51,9 +116,14 @@ impl Cost {
         self.mana_cost = mana_cost;
         self
     }
+
+    pub fn with_exile_from_graveyard(mut self, amount: usize) -> Self {
+        self.exile_from_graveyard = amount;
+        self
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Debug, Serialize, Deserialize)]
 pub enum ManaType {
     White,
     Blue,
@@ -71,7 +141,7 @@ impl Into<Cost> for ManaCost {
 
 impl Into<AbilityCost> for Cost {
     fn into(self) -> AbilityCost {
-        AbilityCost { cost: self, tap: false }
+        AbilityCost { cost: self, ..AbilityCost::empty() }
     }
 }
 
@@ -1,5 +1,7 @@
 use std::{fmt::Debug, vec};
 
+use serde::{Serialize, Deserialize};
+
 use super::prelude::*;
 
 #[derive(Debug)]
@@ -8,20 +10,26 @@ pub enum GameObject {
     Object, // Sorceries/Instants
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum GameObjectID {
     Permanent(PermanentID),
+    Player(PlayerID),
     Object, // Sorceries/Instants
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TurnStep {
     Untap,
     Upkeep,
     Draw,
     FirstMainPhase,
-    Combat,
+    BeginCombat,
+    DeclareAttackers,
+    DeclareBlockers,
+    CombatDamage,
+    EndCombat,
     SecondMainPhase,
+    End,
     Discard,
     CleanUp,
 }
@@ -30,27 +38,38 @@ impl TurnStep {
     pub fn is_main_phase(self) -> bool {
         self == Self::FirstMainPhase || self == Self::SecondMainPhase
     }
+
+    pub fn is_combat(self) -> bool {
+        matches!(self, Self::BeginCombat | Self::DeclareAttackers | Self::DeclareBlockers | Self::CombatDamage | Self::EndCombat)
+    }
 }
 
-pub const DEFAULT_TURN_STRUCTURE: [TurnStep; 8] = [
+pub const DEFAULT_TURN_STRUCTURE: [TurnStep; 13] = [
     TurnStep::Untap,
     TurnStep::Upkeep,
     TurnStep::Draw,
     TurnStep::FirstMainPhase,
-    TurnStep::Combat,
+    TurnStep::BeginCombat,
+    TurnStep::DeclareAttackers,
+    TurnStep::DeclareBlockers,
+    TurnStep::CombatDamage,
+    TurnStep::EndCombat,
     TurnStep::SecondMainPhase,
+    TurnStep::End,
     TurnStep::Discard,
     TurnStep::CleanUp,
 ];
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum EventSource {
     Permanent(PermanentID),
+    /// Combat damage dealt by an attacking or blocking creature.
+    Combat(PermanentID),
     Player(PlayerID),
     GameRule(GameRule),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum GameRule {
 
 
@@ -58,6 +77,10 @@ pub enum GameRule {
     
     /// Destroy because of Damage
     LethalDamage,
+    /// Destroy because of damage from a source with deathtouch
+    DeathtouchDamage,
+    /// Put into the graveyard because of zero or less toughness
+    ZeroToughness,
     /// Lose because of no health
     NoHealth,
     /// A plainswalker dies with no loyalty
@@ -66,8 +89,36 @@ pub enum GameRule {
     CancelOutCounters,
     /// If you draw but can't, then you lose
     CouldntDraw,
-    /// Sagas with no lore counters are sacrificed
+    /// Lose because of ten or more poison counters
+    TooMuchPoison,
+    /// Sagas that have finished their final chapter are sacrificed
     NoLore,
+    /// Auras attached illegally go to the graveyard, and equipment falls off
+    IllegalAttachment,
+    /// A player can't control more than one legendary permanent with the same name
+    LegendRule,
+    /// Only the newest world permanent stays on the battlefield
+    WorldRule,
+    /// Lose because of 21 combat damage from a single commander
+    CommanderDamage,
+    /// Tokens that left the battlefield cease to exist
+    TokenOutsideBattlefield,
+
+
+    // Turn Based Actions
+
+    /// Sagas get a lore counter as they enter, and each precombat main phase
+    SagaLore,
+    /// Discard down to the maximum hand size at the end of the turn
+    MaxHandSize,
+
+
+    // Game Over
+
+    /// Every other player has left the game
+    LastPlayerStanding,
+    /// Another player won the game
+    OpponentWon,
 }
 
 impl Into<EventSource> for GameRule {
@@ -77,7 +128,7 @@ impl Into<EventSource> for GameRule {
 /// This represents any game modification event
 /// that is relevant to other abilities, and could
 /// potentially be modified by them.
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum GameEvent {
     /// Start the given player's turn
     StartTurn(PlayerID),
@@ -94,11 +145,18 @@ pub enum GameEvent {
     /// Draw a card from the top of the deck
     DrawCard(PlayerID),
 
-    /// Play the card. This assumes the cost has alreaady been paid.
-    PlaySpell(AssignedCardPlay),
+    /// The player draws their opening hand, taking mulligans until they
+    /// keep one.
+    OpeningHand(PlayerID),
 
-    /// Activates the ability. This assumes cost has already been paid.
-    ActivateAbility(AssignedAbility),
+    /// The card is discarded from its owner's hand.
+    DiscardCard(CardID, EventSource),
+
+    /// Play the card with the chosen targets, modes and value for X. This assumes the cost has alreaady been paid.
+    PlaySpell(AssignedCardPlay, Vec<Target>, Vec<ChosenMode>, u32),
+
+    /// Activates the ability with the chosen targets and modes. This assumes cost has already been paid.
+    ActivateAbility(AssignedAbility, Vec<Target>, Vec<ChosenMode>),
 
     /// Pays mana out of given player's pool. 
     /// Assumes player can pay it, otherwise its an error
@@ -119,34 +177,123 @@ pub enum GameEvent {
     /// The given permanent was sacrificed 
     Sacrifice(PermanentID, EventSource),
 
+    /// The given permanent is put into the graveyard, without being
+    /// destroyed or sacrificed.
+    PutInGraveyard(PermanentID, EventSource),
+
+    /// Look at the top cards of the library, put any number of them on the
+    /// bottom and the rest back on top in any order.
+    Scry(PlayerID, u32),
+
+    /// Like scry, but the cards go into the graveyard instead of the bottom.
+    Surveil(PlayerID, u32),
+
+    /// Put the top cards of the library into the graveyard.
+    Mill(PlayerID, u32),
+
+    /// Search the library for a card, then shuffle.
+    SearchLibrary(LibrarySearch),
+
+    /// Show the cards to the given players.
+    Reveal(Vec<CardID>, Vec<PlayerID>),
+
+    /// Put the card on top of its owner's library.
+    PutOnTop(CardID, EventSource),
+
+    /// Put the card on the bottom of its owner's library.
+    PutOnBottom(CardID, EventSource),
+
+    /// Shuffle the player's library.
+    Shuffle(PlayerID),
+
+    /// Exile the card from the zone it is in. Permanents are exiled
+    /// through their own events instead.
+    ExileCard(CardID, EventSource),
+
+    /// A double-faced permanent turns over to its other face.
+    Transform(PermanentID, EventSource),
+
+    /// A flip card's permanent turns upside down, to its second face.
+    Flip(PermanentID, EventSource),
+
+    /// Exile the card face down. Nobody can see it unless it is revealed.
+    ExileFaceDown(CardID, EventSource),
+
+    /// Attach the first permanent (an aura or equipment) to the second
+    Attach(PermanentID, PermanentID, EventSource),
+
+    /// The given aura or equipment becomes unattached
+    Unattach(PermanentID, EventSource),
+
     /// The given player will lose
     Lose(PlayerID, EventSource),
 
+    /// The given player will win, and everyone else will lose
+    Win(PlayerID, EventSource),
+
     /// Add counters of given kind on object
     AddCounters(GameObjectID, CounterType, u32, EventSource),
 
     /// Remove counters of given kind on object
     RemoveCounters(GameObjectID, CounterType, u32, EventSource),
 
+    /// Deal damage to a permanent or player
+    DealDamage(GameObjectID, u32, EventSource),
+
+    /// The player gains life, unless an effect says they can't
+    GainLife(PlayerID, u32, EventSource),
+
+    /// The permanent gets +X/+Y until the turn is cleaned up.
+    BoostUntilEndOfTurn(PermanentID, i32, i32, EventSource),
+
+    /// The active player chooses which creatures attack, and what each
+    /// of them attacks.
+    DeclareAttackers(PlayerID),
+
+    /// The creature attacks the player or planeswalker.
+    Attacks(PermanentID, AttackTarget),
+
+    /// The defending player chooses which creatures block.
+    DeclareBlockers(PlayerID),
+
+    /// The first creature blocks the second, attacking one.
+    Blocks(PermanentID, PermanentID),
+
+    /// Attacking and blocking creatures deal their combat damage.
+    CombatDamage,
+
     /// Permanent is registered, then Enter the Battlefield event is fired.
     RegisterPermanent(Permanent),
 
     /// The permanent has entered the battlefield.
     EnterTheBattleField(PermanentID),
 
+    /// The token, which left the battlefield, ceases to exist.
+    CeaseToExist(PermanentID, EventSource),
+
     /// The game gives the player priority
     GivePriority(PlayerID),
 
-    /// The game tries to pass prioirity. It might 
-    /// resolve into a 'TryResolveStackObject' action.
+    /// The player passes priority. Once every player has passed in 
+    /// succession, it resolves into a 'TryResolveStackObject' action, or 
+    /// the step ends if the stack is empty.
     PassPriority(PlayerID),
 
     /// The game tries to resolve the top object
     /// on the stack. If stack is empty, does nothing
     TryResolveStackObject,
 
+    /// Remove the spell or ability from the stack without resolving it.
+    /// Countered spells are put into their owner's graveyard.
+    CounterObject(ObjectID, EventSource),
+
     /// The game moves onto the next step
     NextStep,
+
+    /// All of these events happen at the same time. They are all
+    /// replaced, then all applied, and only then does anything
+    /// they triggered happen.
+    Simultaneously(Vec<GameEvent>),
 }
 
 impl GameEvent {
@@ -161,12 +308,101 @@ impl GameEvent {
     pub fn replaced(self, by: Self) -> ListenResult {
         ListenResult::Replaced(vec![by])
     }
+
+    /// The player affected by this event. They choose the order that
+    /// replacement effects are applied in.
+    pub fn affected_player(&self, game: &Game) -> Option<PlayerID> {
+        use GameEvent::*;
+        let perm_owner = |perm: &PermanentID| game.battlefield.get(perm).map(|perm| perm.owner);
+
+        match self {
+            StartTurn(player)
+            | DrawCard(player)
+            | OpeningHand(player)
+            | PayMana(player, _)
+            | AddMana(player, _, _)
+            | GainLife(player, _, _)
+            | Lose(player, _)
+            | Win(player, _)
+            | GivePriority(player)
+            | PassPriority(player)
+            | Scry(player, _)
+            | Surveil(player, _)
+            | Mill(player, _)
+            | Shuffle(player)
+            | DeclareAttackers(player)
+            | DeclareBlockers(player) => Some(*player),
+
+            SearchLibrary(search) => Some(search.player),
+            Reveal(cards, _) => cards.first().map(|card| game.get(*card).owner),
+            PutOnTop(card, _) | PutOnBottom(card, _) | DiscardCard(card, _) => Some(game.get(*card).owner),
+
+            UntapPerm(perm)
+            | TapPerm(perm)
+            | Destroy(perm, _)
+            | Sacrifice(perm, _)
+            | PutInGraveyard(perm, _)
+            | Attach(perm, _, _)
+            | Unattach(perm, _)
+            | Transform(perm, _)
+            | Flip(perm, _)
+            | BoostUntilEndOfTurn(perm, ..)
+            | Attacks(perm, _)
+            | Blocks(perm, _)
+            | EnterTheBattleField(perm) => perm_owner(perm),
+
+            AddCounters(object, ..)
+            | RemoveCounters(object, ..)
+            | DealDamage(object, ..) => match object {
+                GameObjectID::Permanent(perm) => perm_owner(perm),
+                GameObjectID::Player(player) => Some(*player),
+                GameObjectID::Object => None,
+            },
+
+            PlaySpell(as_card_play, ..) => Some(game.get(as_card_play.card).owner),
+            ExileCard(card, _) | ExileFaceDown(card, _) => Some(game.get(*card).owner),
+            CounterObject(object, _) => game.game_stack
+                .iter()
+                .find(|stack_object| stack_object.id == *object)
+                .map(|stack_object| stack_object.controller),
+            ActivateAbility(as_ability, ..) => perm_owner(&as_ability.perm),
+            RegisterPermanent(perm) => Some(perm.owner),
+            CeaseToExist(token, _) => game.tokens_elsewhere
+                .iter()
+                .find(|other| other.id == *token)
+                .map(|token| token.owner),
+            LegendConflict(perms) => perms.first().and_then(perm_owner),
+
+            Step(_) | TryResolveStackObject | NextStep | CombatDamage | Simultaneously(_) => None,
+        }
+    }
 }
 
-#[derive(Debug)]
+/// An event waiting on the event stack, along with the replacement
+/// effects that have already modified it. Each replacement effect 
+/// applies to an event at most once.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueuedEvent {
+    pub event: GameEvent,
+    pub applied_replacements: Vec<AssignedAbility>,
+}
+
+impl QueuedEvent {
+    pub fn new(event: GameEvent) -> Self {
+        Self {
+            event,
+            applied_replacements: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum CounterType {
     PlusOnePlusOne,
     MinusOneMinusOne,
+    Loyalty,
+    Lore,
+    Poison,
 }
 
 /// This represents any read of game state that could
@@ -177,6 +413,13 @@ pub enum GameQuery {
     PermAbilities(PermAbilityQuery),
     ObservePerm(ObservePermQuery),
     CardPlays(CardPlaysQuery),
+    WinLose(WinLoseQuery),
+    Counterable(CounterableQuery),
+    Cost(CostQuery),
+    ObservePlayer(ObservePlayerQuery),
+    CanAttack(CanAttackQuery),
+    CanBlock(CanBlockQuery),
+    Targetable(TargetableQuery),
 }
 
 
@@ -224,6 +467,163 @@ impl CardPlaysQuery {
 }
 
 
+/// Whether the player is currently able to win or lose the game.
+#[derive(Debug)]
+pub struct WinLoseQuery {
+    pub player: PlayerID,
+    pub can_win: bool,
+    pub can_lose: bool,
+}
+
+impl WinLoseQuery {
+    pub fn new(player: PlayerID) -> Self {
+        WinLoseQuery {
+            player,
+            can_win: true,
+            can_lose: true,
+        }
+    }
+}
+
+/// Whether the spell or ability on the stack can be countered.
+#[derive(Debug)]
+pub struct CounterableQuery {
+    pub id: ObjectID,
+    pub can_be_countered: bool,
+}
+
+impl CounterableQuery {
+    pub fn new(id: ObjectID) -> Self {
+        CounterableQuery {
+            id,
+            can_be_countered: true,
+        }
+    }
+}
+
+/// What is being paid for in a cost query.
+#[derive(Clone, Copy, Debug)]
+pub enum CostSource {
+    CardPlay(AssignedCardPlay),
+    Ability(AssignedAbility),
+}
+
+/// The total cost to cast a spell or activate an ability. Any {X} has 
+/// already been replaced by its chosen value, so effects that make things
+/// cost more or less only change the generic mana. They add to `increase`
+/// and `reduction` rather than changing the cost, since every increase
+/// applies before any reduction.
+#[derive(Debug)]
+pub struct CostQuery {
+    pub source: CostSource,
+    pub player: PlayerID,
+    pub x: u32,
+    pub cost: Cost,
+    pub increase: usize,
+    pub reduction: usize,
+}
+
+impl CostQuery {
+    pub fn new(source: CostSource, player: PlayerID, x: u32, cost: Cost) -> Self {
+        CostQuery {
+            source,
+            player,
+            x,
+            cost,
+            increase: 0,
+            reduction: 0,
+        }
+    }
+
+    /// The cost with every increase and then every reduction applied.
+    pub fn total(mut self) -> Cost {
+        self.cost.increase_generic(self.increase);
+        self.cost.reduce_generic(self.reduction);
+        self.cost
+    }
+}
+
+/// A player's characteristics, as effects change them.
+#[derive(Debug)]
+pub struct ObservePlayerQuery {
+    pub player: PlayerID,
+    pub life_total: i32,
+    /// How many cards are in the player's hand.
+    pub hand_size: usize,
+    /// The player discards down to this many cards at the end of their turn.
+    pub max_hand_size: Option<usize>,
+    /// How many lands the player can play each turn.
+    pub land_plays: u32,
+    pub can_gain_life: bool,
+}
+
+impl ObservePlayerQuery {
+    pub fn new(player: PlayerID) -> Self {
+        ObservePlayerQuery {
+            player,
+            life_total: 0,
+            hand_size: 0,
+            max_hand_size: None,
+            land_plays: 0,
+            can_gain_life: true,
+        }
+    }
+}
+
+/// Whether the creature can attack the player or planeswalker.
+#[derive(Debug)]
+pub struct CanAttackQuery {
+    pub attacker: PermanentID,
+    pub target: AttackTarget,
+    pub can_attack: bool,
+}
+
+impl CanAttackQuery {
+    pub fn new(attacker: PermanentID, target: AttackTarget) -> Self {
+        CanAttackQuery {
+            attacker,
+            target,
+            can_attack: true,
+        }
+    }
+}
+
+/// Whether the first creature can block the second, attacking one.
+#[derive(Debug)]
+pub struct CanBlockQuery {
+    pub blocker: PermanentID,
+    pub attacker: PermanentID,
+    pub can_block: bool,
+}
+
+impl CanBlockQuery {
+    pub fn new(blocker: PermanentID, attacker: PermanentID) -> Self {
+        CanBlockQuery {
+            blocker,
+            attacker,
+            can_block: true,
+        }
+    }
+}
+
+/// Whether the player can choose the target for their spell or ability.
+#[derive(Debug)]
+pub struct TargetableQuery {
+    pub player: PlayerID,
+    pub target: Target,
+    pub targetable: bool,
+}
+
+impl TargetableQuery {
+    pub fn new(player: PlayerID, target: Target) -> Self {
+        TargetableQuery {
+            player,
+            target,
+            targetable: true,
+        }
+    }
+}
+
 
 pub trait GameQueryVariant: Debug + TryFrom<GameQuery> + Into<GameQuery> {}
 
@@ -253,3 +653,10 @@ macro_rules! make_game_query_variant {
 make_game_query_variant!(PermAbilityQuery, PermAbilities);
 make_game_query_variant!(ObservePermQuery, ObservePerm);
 make_game_query_variant!(CardPlaysQuery, CardPlays);
+make_game_query_variant!(WinLoseQuery, WinLose);
+make_game_query_variant!(CounterableQuery, Counterable);
+make_game_query_variant!(CostQuery, Cost);
+make_game_query_variant!(ObservePlayerQuery, ObservePlayer);
+make_game_query_variant!(CanAttackQuery, CanAttack);
+make_game_query_variant!(CanBlockQuery, CanBlock);
+make_game_query_variant!(TargetableQuery, Targetable);
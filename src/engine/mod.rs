@@ -15,5 +15,16 @@ pub mod game_facade;
 pub mod state_based_actions;
 pub mod counters;
 pub mod object;
+pub mod game_result;
+pub mod target;
+pub mod delayed_trigger;
+pub mod keyword;
+pub mod mode;
+pub mod library;
+pub mod combat;
+pub mod commander;
+pub mod game_config;
+pub mod replay;
+pub mod save;
 
 pub mod prelude;
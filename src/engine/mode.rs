@@ -0,0 +1,48 @@
+use serde::{Serialize, Deserialize};
+
+use super::prelude::*;
+
+/// The modes of a "choose one or more" spell or ability, and how many
+/// of them have to be chosen.
+#[derive(Clone, Debug)]
+pub struct ModeRequirements {
+    pub descriptions: Vec<String>,
+    pub min: usize,
+    pub max: usize,
+    /// Whether the same mode may be chosen more than once
+    pub repeatable: bool,
+}
+
+impl ModeRequirements {
+    pub fn choose(min: usize, max: usize, descriptions: Vec<String>) -> Self {
+        Self {
+            descriptions,
+            min,
+            max,
+            repeatable: false,
+        }
+    }
+
+    pub fn choose_one(descriptions: Vec<String>) -> Self {
+        Self::choose(1, 1, descriptions)
+    }
+
+    pub fn repeatable(mut self) -> Self {
+        self.repeatable = true;
+        self
+    }
+}
+
+/// A mode that was chosen while casting or activating, along with its
+/// own targets.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChosenMode {
+    pub mode: usize,
+    pub targets: Vec<Target>,
+}
+
+impl ChosenMode {
+    pub fn new(mode: usize, targets: Vec<Target>) -> Self {
+        Self { mode, targets }
+    }
+}
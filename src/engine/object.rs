@@ -1,19 +1,78 @@
-use super::{prelude::*, permanent};
+use serde::{Serialize, Deserialize};
 
+use super::prelude::*;
+
+pub type ObjectID = ID<Object>;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Object {
+    pub id: ObjectID,
     pub card: Option<CardID>,
+    pub controller: PlayerID,
+    pub targets: Vec<Target>,
+    /// The chosen modes of a modal spell, each with its own targets.
+    pub modes: Vec<ChosenMode>,
+    /// The value chosen for X while casting, or zero.
+    pub x: u32,
+    /// Where the card goes when it leaves the stack, if not the graveyard.
+    pub leaves_stack_to: Option<PlayZone>,
     pub resolve: ObjectResolve,
 } 
 
+#[derive(Clone, Serialize, Deserialize)]
 pub enum ObjectResolve {
     CreateLand(Permanent),
     CreatePerm(Permanent),
-    AbilityActivate(AbilityID) 
+    /// An instant or sorcery. The card play it was cast with decides 
+    /// what happens when it resolves.
+    Spell(AssignedCardPlay),
+    AbilityActivate(AssignedAbility),
+    DelayedTrigger(DelayedTrigger),
 }
 
-impl From<Permanent> for Object {
-    fn from(perm: Permanent) -> Self {
+impl Object {
+    pub fn from_delayed_trigger(delayed: DelayedTrigger, id: ObjectID) -> Self {
+        Object {
+            id,
+            card: None,
+            controller: delayed.controller,
+            targets: vec![],
+            modes: vec![],
+            x: 0,
+            leaves_stack_to: None,
+            resolve: ObjectResolve::DelayedTrigger(delayed),
+        }
+    }
+
+    pub fn from_ability(ability: AssignedAbility, controller: PlayerID, id: ObjectID) -> Self {
+        Object {
+            id,
+            card: None,
+            controller,
+            targets: vec![],
+            modes: vec![],
+            x: 0,
+            leaves_stack_to: None,
+            resolve: ObjectResolve::AbilityActivate(ability),
+        }
+    }
+
+    pub fn from_spell(as_card_play: AssignedCardPlay, controller: PlayerID, id: ObjectID) -> Self {
+        Object {
+            id,
+            card: Some(as_card_play.card),
+            controller,
+            targets: vec![],
+            modes: vec![],
+            x: 0,
+            leaves_stack_to: None,
+            resolve: ObjectResolve::Spell(as_card_play),
+        }
+    }
+
+    pub fn from_perm(perm: Permanent, id: ObjectID) -> Self {
         let card = perm.card;
+        let controller = perm.owner;
 
         let resolve = if perm.type_line.is(CardType::Land) {
             ObjectResolve::CreateLand(perm)
@@ -22,8 +81,25 @@ impl From<Permanent> for Object {
         };
 
         Object {
+            id,
             card,
+            controller,
+            targets: vec![],
+            modes: vec![],
+            x: 0,
+            leaves_stack_to: None,
             resolve,
         }
     }
+
+    /// The targets of the object, including those of its chosen modes.
+    pub fn all_targets(&self) -> impl Iterator<Item = &Target> {
+        self.targets
+            .iter()
+            .chain(self.modes.iter().flat_map(|mode| mode.targets.iter()))
+    }
+
+    pub fn is_spell(&self) -> bool {
+        self.card.is_some()
+    }
 }
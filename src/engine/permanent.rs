@@ -1,16 +1,19 @@
 use std::fmt::Debug;
 
+use serde::{Serialize, Deserialize};
+
 use crate::engine::prelude::*;
 
 use super::util::id::ID;
 
 pub type PermanentID = ID<Permanent>;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Permanent {
     pub name: String,
     pub flavor: String,
     pub type_line: TypeLine,
+    pub colors: Vec<ManaType>,
     pub card: Option<CardID>,
     pub is_token: bool,
     pub owner: PlayerID,
@@ -21,9 +24,22 @@ pub struct Permanent {
     pub summoning_sickness: bool,
     pub damage: i32,
     pub counters: Counters,
+    /// Whether a loyalty ability of this permanent was activated this turn.
+    pub loyalty_activated: bool,
+    /// For auras, the kind of permanent it can enchant.
+    pub enchant: Option<CardType>,
+    pub attached_to: Option<PermanentID>,
+    pub attachments: Vec<PermanentID>,
+    pub keywords: Vec<Keyword>,
+    /// Whether any of the damage marked on this was dealt by a source with deathtouch.
+    pub deathtouch_damage: bool,
+    /// When this entered the battlefield. Later permanents have larger timestamps.
+    pub timestamp: usize,
+    /// Which face of its card is up.
+    pub face: usize,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PowerToughness {
     pub power: i32,
     pub toughness: i32,
@@ -47,24 +63,90 @@ impl Permanent {
         self.tapped = false;
     }
 
-    pub fn from_card(card: &Card, id: PermanentID, owner: PlayerID) -> Self {
+    pub fn token(name: String, type_line: TypeLine, power_toughness: Option<PowerToughness>, id: PermanentID, owner: PlayerID) -> Self {
         Self {
-            name: card.attrs.name.clone(),
-            flavor: card.attrs.flavor.clone(),
-            card: Some(card.id),
-            is_token: false,
+            name,
+            flavor: String::new(),
+            card: None,
+            is_token: true,
             owner,
-            power_toughness: card.attrs.power_toughness.clone(),
-            type_line: card.attrs.type_line.clone(),
+            power_toughness,
+            type_line,
+            colors: vec![],
             id,
-            abilities: card.perm_abilities.clone(),
+            abilities: vec![],
             tapped: false,
             summoning_sickness: true,
             damage: 0,
             counters: Counters::new(),
+            loyalty_activated: false,
+            enchant: None,
+            attached_to: None,
+            attachments: vec![],
+            keywords: vec![],
+            deathtouch_damage: false,
+            timestamp: 0,
+            face: 0,
         }
     }
 
+    pub fn from_card(card: &Card, id: PermanentID, owner: PlayerID) -> Self {
+        Self::from_card_face(card, 0, id, owner)
+    }
+
+    pub fn from_card_face(card: &Card, face: usize, id: PermanentID, owner: PlayerID) -> Self {
+        let attrs = &card.face(face).attrs;
+        let mut perm = Self::token(attrs.name.clone(), attrs.type_line.clone(), None, id, owner);
+        perm.card = Some(card.id);
+        perm.is_token = false;
+        perm.tapped = attrs.enters_tapped;
+        perm.counters = Counters::new().with_loyalty(attrs.loyalty.unwrap_or(0));
+        perm.set_face(card, face);
+        perm
+    }
+
+    /// Turns the given face of the card up, taking on its characteristics.
+    /// Damage, counters and attachments stay.
+    pub fn set_face(&mut self, card: &Card, face: usize) {
+        let Face { attrs, perm_abilities } = card.face(face);
+        self.face = face;
+        self.name = attrs.name.clone();
+        self.flavor = attrs.flavor.clone();
+        self.type_line = attrs.type_line.clone();
+        self.colors = attrs.colors();
+        self.power_toughness = attrs.power_toughness.clone();
+        self.abilities = perm_abilities.clone();
+        self.enchant = attrs.enchant.clone();
+        self.keywords = attrs.keywords.clone();
+    }
+
+    pub fn has_keyword(&self, keyword: Keyword) -> bool {
+        self.keywords.contains(&keyword)
+    }
+
+    pub fn is_aura(&self) -> bool {
+        self.type_line.is(AURA)
+    }
+
+    pub fn is_equipment(&self) -> bool {
+        self.type_line.is(EQUIPMENT)
+    }
+
+    /// Whether the given permanent is something this aura or equipment
+    /// can legally be attached to.
+    pub fn can_attach_to(&self, host: &Permanent) -> bool {
+        if host.id == self.id {
+            return false
+        }
+
+        if self.is_aura() {
+            self.enchant.as_ref().is_some_and(|card_type| host.type_line.is(card_type.clone()))
+        } else if self.is_equipment() {
+            host.type_line.is(CardType::Creature)
+        } else {
+            false
+        }
+    }
 }
 
 impl Debug for Permanent {
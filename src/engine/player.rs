@@ -1,20 +1,35 @@
+use serde::{Serialize, Deserialize};
+
 use crate::engine::prelude::*;
 
 use super::util::id::ID;
 
 pub type PlayerID = ID<Player>;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Player {
     pub id: PlayerID,
     pub life_total: i32,
+    pub poison_counters: u32,
     pub mana_pool: Vec<ManaType>,
+    /// Set once the player has lost and left the game.
+    pub eliminated: bool,
+    /// Set when the player tried to draw from an empty library. They
+    /// lose when state based actions are next checked, which clears it.
+    pub drew_from_empty_library: bool,
+    /// How many lands the player has played this turn.
+    pub lands_played: u32,
 }
 
 impl Player {
-    pub fn new(id: PlayerID) -> Self {
+    pub fn new(id: PlayerID, life_total: i32) -> Self {
         Self {
-            life_total: 20,
+            life_total,
+            poison_counters: 0,
             mana_pool: vec![],
+            eliminated: false,
+            drew_from_empty_library: false,
+            lands_played: 0,
             id,
         }
     }
@@ -15,4 +15,13 @@ pub use crate::engine::{
     game_facade::*,
     counters::*,
     object::*,
+    game_result::*,
+    target::*,
+    delayed_trigger::*,
+    keyword::*,
+    mode::*,
+    library::*,
+    combat::*,
+    commander::*,
+    game_config::*,
 };
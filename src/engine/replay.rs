@@ -0,0 +1,287 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    sync::{Arc, Mutex},
+};
+
+use log::info;
+
+use crate::client::{PlayerActionRequest, PlayerActionResponse, PlayerInterface};
+
+use super::prelude::*;
+
+pub const REPLAY_VERSION: u32 = 1;
+const REPLAY_HEADER: &str = "tabulator-arcana replay";
+
+/// Everything needed to play a game again exactly as it went: the seed
+/// for the random number generator, the rules and decks, and every
+/// decision the players made.
+pub struct Replay {
+    pub seed: u64,
+    pub config: GameConfig,
+    /// The names the cards of each deck were made from, in order.
+    pub decks: Vec<Vec<String>>,
+    pub decisions: Vec<Decision>,
+}
+
+/// One choice a player made. Options are kept in their debug form, so
+/// they can be compared with what the game offers during playback.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decision {
+    pub player: String,
+    pub options: Vec<String>,
+    pub chosen: usize,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// The line number, and what was wrong with it.
+    Parse(usize, String),
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+/// Passes every decision through to another interface, and writes it to
+/// the replay file as soon as it is made. Games that crash can still be
+/// replayed up to the crash.
+#[derive(Clone)]
+pub struct Recorder {
+    inner: Box<dyn PlayerInterface>,
+    file: Arc<Mutex<File>>,
+}
+
+/// Makes the recorded decisions instead of asking anyone. Panics as soon
+/// as the game asks for something else than what was recorded.
+#[derive(Clone)]
+pub struct Playback {
+    decisions: Arc<Vec<Decision>>,
+    next: usize,
+}
+
+impl Decision {
+    pub fn new(player: PlayerID, options: &PlayerActionRequest, chosen: usize) -> Self {
+        Self {
+            player: format!("{:?}", player),
+            options: options.iter().map(|option| format!("{:?}", option)).collect(),
+            chosen,
+        }
+    }
+
+    fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "decision {} {} {}", self.player, self.chosen, self.options.len())?;
+        for option in &self.options {
+            writeln!(out, "option {}", option)?;
+        }
+        Ok(())
+    }
+}
+
+impl Replay {
+    pub fn load(path: &str) -> Result<Self, ReplayError> {
+        let lines: Vec<String> = BufReader::new(File::open(path)?).lines().collect::<Result<_, _>>()?;
+        let mut lines = lines.iter().enumerate().map(|(i, line)| (i + 1, line.as_str())).peekable();
+        let lines = &mut lines;
+
+        let (number, version) = next_line(lines, REPLAY_HEADER)?;
+        if version != REPLAY_VERSION.to_string() {
+            return Err(ReplayError::Parse(number, format!("Can't read version {} replays", version)))
+        }
+        let (number, seed) = next_line(lines, "seed")?;
+        let seed = seed.parse().map_err(|_| ReplayError::Parse(number, "Expected a seed".into()))?;
+        let (number, config) = next_line(lines, "config")?;
+        let config = ron::from_str(config).map_err(|error| ReplayError::Parse(number, error.to_string()))?;
+        let (number, players) = next_line(lines, "decks")?;
+        let players = players.parse().map_err(|_| ReplayError::Parse(number, "Expected a number of decks".into()))?;
+
+        let mut decks = vec![];
+        for _ in 0..players {
+            let (_, deck) = next_line(lines, "deck")?;
+            decks.push(deck.split('|').filter(|name| !name.is_empty()).map(String::from).collect());
+        }
+
+        // Decisions go on until the file ends, but only between two of them
+        let mut decisions = vec![];
+        while lines.peek().is_some() {
+            let (number, decision) = next_line(lines, "decision")?;
+            let parts: Vec<_> = decision.split_whitespace().collect();
+            let [player, chosen, count] = parts[..] else {
+                return Err(ReplayError::Parse(number, "Expected a player, a choice and a number of options".into()))
+            };
+            let parse = |value: &str| value.parse().map_err(|_| ReplayError::Parse(number, format!("Expected a number, got {}", value)));
+            let (chosen, count) = (parse(chosen)?, parse(count)?);
+
+            let options = (0..count)
+                .map(|_| next_line(lines, "option").map(|(_, option)| option.to_string()))
+                .collect::<Result<_, _>>()?;
+            decisions.push(Decision { player: player.to_string(), options, chosen });
+        }
+
+        Ok(Self { seed, config, decks, decisions })
+    }
+
+    /// Sets up a game the way the recorded one started, playing back the
+    /// recorded decisions. The decks have to be made from `self.decks`.
+    pub fn game(&self, decks: Vec<Vec<LatentCard>>) -> Game {
+        Game::new(decks, self.config.clone())
+            .with_seed(self.seed)
+            .with_interface(Box::new(Playback::new(self.decisions.clone())))
+    }
+}
+
+/// The next line, which has to start with what is expected. Returns its
+/// line number and the rest of it.
+fn next_line<'a>(lines: &mut impl Iterator<Item = (usize, &'a str)>, expected: &str) -> Result<(usize, &'a str), ReplayError> {
+    let (number, line) = lines.next().ok_or(ReplayError::Parse(0, format!("Expected {}, but the file ended", expected)))?;
+    let rest = line.strip_prefix(expected).ok_or(ReplayError::Parse(number, format!("Expected {}", expected)))?;
+    Ok((number, rest.trim()))
+}
+
+impl Recorder {
+    /// Starts a replay file for the game. The decks are the names the
+    /// game's decks were made from.
+    pub fn create(path: &str, game: &Game, decks: &[Vec<String>], inner: Box<dyn PlayerInterface>) -> io::Result<Self> {
+        let mut file = File::create(path)?;
+        writeln!(file, "{} {}", REPLAY_HEADER, REPLAY_VERSION)?;
+        writeln!(file, "seed {}", game.seed)?;
+        let config = ron::to_string(&game.config).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        writeln!(file, "config {}", config)?;
+        writeln!(file, "decks {}", decks.len())?;
+        for deck in decks {
+            writeln!(file, "deck {}", deck.join("|"))?;
+        }
+        file.flush()?;
+
+        Ok(Self { inner, file: Arc::new(Mutex::new(file)) })
+    }
+}
+
+impl PlayerInterface for Recorder {
+    fn choose_options(&mut self, player: PlayerID, options: PlayerActionRequest) -> PlayerActionResponse {
+        let response = self.inner.choose_options(player, options.clone());
+
+        let response_name = format!("{:?}", response);
+        let chosen = options
+            .iter()
+            .position(|option| format!("{:?}", option) == response_name)
+            .unwrap_or_else(|| panic!("{:?} responded with {}, which wasn't one of the options", player, response_name));
+
+        let mut file = self.file.lock().expect("replay file lock was poisoned");
+        Decision::new(player, &options, chosen)
+            .write(&mut *file)
+            .and_then(|_| file.flush())
+            .expect("couldn't write to the replay file");
+        response
+    }
+
+    fn update_state(&mut self, player: PlayerID, snapshot: GameStateSnapshot) {
+        self.inner.update_state(player, snapshot);
+    }
+}
+
+impl Playback {
+    pub fn new(decisions: Vec<Decision>) -> Self {
+        Self { decisions: Arc::new(decisions), next: 0 }
+    }
+}
+
+impl PlayerInterface for Playback {
+    fn choose_options(&mut self, player: PlayerID, options: PlayerActionRequest) -> PlayerActionResponse {
+        let Some(recorded) = self.decisions.get(self.next) else {
+            panic!("Replay ran out after {} decisions, but the game asked {:?} for another", self.next, player)
+        };
+
+        let asked = Decision::new(player, &options, recorded.chosen);
+        if asked != *recorded {
+            panic!(
+                "Replay diverged at decision {}: {} was recorded choosing from {:?}, but {} was asked to choose from {:?}",
+                self.next, recorded.player, recorded.options, asked.player, asked.options)
+        }
+
+        info!("Replaying decision {} for {:?}", self.next, player);
+        self.next += 1;
+        options.into_iter().nth(recorded.chosen).expect("Recorded choice should be one of the options")
+    }
+
+    fn update_state(&mut self, _player: PlayerID, _snapshot: GameStateSnapshot) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use crate::{client::FirstOption, impls::cards::get_card};
+
+    use super::*;
+
+    fn decks() -> Vec<Vec<String>> {
+        let cards = || ["mountain", "mountain", "goblin assailant", "goblin assailant"]
+            .repeat(2)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        vec![cards(), cards()]
+    }
+
+    fn build(decks: &[Vec<String>]) -> Vec<Vec<LatentCard>> {
+        decks.iter().map(|deck| deck.iter().map(|name| get_card(name)).collect()).collect()
+    }
+
+    fn temp_path(name: &str) -> String {
+        env::temp_dir()
+            .join(format!("{}-{}", std::process::id(), name))
+            .to_string_lossy()
+            .into()
+    }
+
+    #[test]
+    fn playback_repeats_the_recorded_game() {
+        let path = temp_path("recorded.replay");
+        let decks = decks();
+        let game = Game::new(build(&decks), GameConfig::constructed());
+        let recorder = Recorder::create(&path, &game, &decks, Box::new(FirstOption)).unwrap();
+        let recorded = game.with_interface(Box::new(recorder)).run();
+
+        let replay = Replay::load(&path);
+        fs::remove_file(&path).unwrap();
+        let replay = replay.unwrap();
+        assert!(!replay.decisions.is_empty());
+        assert_eq!(replay.decks, decks);
+
+        // Playback panics if the game asks for anything that wasn't recorded
+        let replayed = replay.game(build(&replay.decks)).run();
+        assert_eq!(format!("{:?}", recorded), format!("{:?}", replayed));
+    }
+
+    fn load_decisions(name: &str, decisions: &[&str]) -> Result<Replay, ReplayError> {
+        let path = temp_path(name);
+        let mut lines = vec![
+            format!("{} {}", REPLAY_HEADER, REPLAY_VERSION),
+            "seed 1".into(),
+            format!("config {}", ron::to_string(&GameConfig::constructed()).unwrap()),
+            "decks 0".into(),
+        ];
+        lines.extend(decisions.iter().map(|line| line.to_string()));
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let replay = Replay::load(&path);
+        fs::remove_file(&path).unwrap();
+        replay
+    }
+
+    #[test]
+    fn replay_ending_in_the_middle_of_a_decision_is_an_error() {
+        let replay = load_decisions("truncated.replay", &["decision PlayerID 0 2", "option Pass"]);
+        assert!(matches!(replay, Err(ReplayError::Parse(0, _))));
+    }
+
+    #[test]
+    fn replay_with_a_broken_decision_is_an_error() {
+        let replay = load_decisions("broken.replay", &["decision PlayerID 0 1", "option Pass", "nonsense"]);
+        assert!(matches!(replay, Err(ReplayError::Parse(7, _))));
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    fs,
+    io,
+    sync::Arc,
+};
+
+use ron::ser::PrettyConfig;
+
+use super::prelude::*;
+
+pub const SAVE_VERSION: u32 = 1;
+const SAVE_HEADER: &str = "tabulator-arcana save";
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Format(String),
+    /// The file was saved in a version this can't read.
+    Version(String),
+    /// A card was made from a definition that can't be looked up.
+    UnknownCard(String),
+}
+
+impl From<io::Error> for SaveError {
+    fn from(error: io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+impl Game {
+    /// Writes the whole game to the file. Abilities and card plays are
+    /// closures, so only the definition each card was made from is saved,
+    /// along with the IDs its abilities and card plays were given.
+    pub fn save(&self, path: &str) -> Result<(), SaveError> {
+        fs::write(path, self.save_to_string()?)?;
+        Ok(())
+    }
+
+    fn save_to_string(&self) -> Result<String, SaveError> {
+        let body = ron::ser::to_string_pretty(self, PrettyConfig::default())
+            .map_err(|error| SaveError::Format(error.to_string()))?;
+        Ok(format!("{} {}\n{}", SAVE_HEADER, SAVE_VERSION, body))
+    }
+
+    /// Reads a game written by `save`. Each card's abilities and card plays
+    /// are made again from its definition, which `definitions` looks up.
+    /// The game plays on with `resume`, after connecting it to its players.
+    pub fn load(path: &str, definitions: impl Fn(&str) -> Option<LatentCard>) -> Result<Self, SaveError> {
+        Self::load_from_string(&fs::read_to_string(path)?, definitions)
+    }
+
+    fn load_from_string(contents: &str, definitions: impl Fn(&str) -> Option<LatentCard>) -> Result<Self, SaveError> {
+        let (header, body) = contents.split_once('\n').unwrap_or((contents, ""));
+        let version = header
+            .strip_prefix(SAVE_HEADER)
+            .ok_or(SaveError::Format("Not a saved game".into()))?
+            .trim();
+        if version != SAVE_VERSION.to_string() {
+            return Err(SaveError::Version(version.to_string()))
+        }
+
+        let mut game: Game = ron::from_str(body).map_err(|error| SaveError::Format(error.to_string()))?;
+        let cards: Vec<Card> = game.cards.all_cards().cloned().collect();
+        for card in &cards {
+            let base = Some(&card.definition)
+                .filter(|definition| !definition.is_empty())
+                .and_then(|definition| definitions(definition))
+                .ok_or_else(|| SaveError::UnknownCard(card.definition.clone()))?;
+            game.restore_card(card, base)?;
+        }
+        Ok(game)
+    }
+
+    /// Registers the abilities and card plays of the card's definition
+    /// again, under the IDs the card has for them.
+    fn restore_card(&mut self, card: &Card, base: LatentCard) -> Result<(), SaveError> {
+        let LatentCard { perm_abilities, card_plays, other_faces, .. } = base;
+        let faces: Vec<_> = std::iter::once(perm_abilities)
+            .chain(other_faces.into_iter().map(|face| face.perm_abilities))
+            .collect();
+
+        let matches_definition = faces.len() == card.faces.len()
+            && card_plays.len() == card.card_plays.len()
+            && faces.iter().zip(card.faces.iter()).all(|(abilities, face)| abilities.len() == face.perm_abilities.len());
+        if !matches_definition {
+            return Err(SaveError::Format(format!("{:?} doesn't match the definition {}", card.id, card.definition)))
+        }
+
+        let abilities = Arc::make_mut(&mut self.abilities);
+        for (base_abilities, face) in faces.into_iter().zip(card.faces.iter()) {
+            for (ability, id) in base_abilities.into_iter().zip(&face.perm_abilities) {
+                abilities.insert(*id, Ability::new(ability, *id));
+            }
+        }
+
+        let card_play_map = Arc::make_mut(&mut self.card_plays);
+        for (card_play, id) in card_plays.into_iter().zip(&card.card_plays) {
+            card_play_map.insert(*id, card_play);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::impls::cards::try_get_card;
+
+    use super::*;
+
+    fn game() -> Game {
+        let deck = || ["mountain", "chandra acolyte of flame", "goblin assailant"]
+            .into_iter()
+            .map(|name| try_get_card(name).unwrap())
+            .collect();
+        Game::new(vec![deck(), deck()], GameConfig::constructed())
+    }
+
+    #[test]
+    fn saving_a_loaded_game_saves_the_same_game() {
+        let mut game = game();
+
+        // Chandra's temporary Elementals left a delayed trigger behind
+        let chandra = game.cards
+            .all_cards()
+            .find(|card| card.definition == "chandra acolyte of flame")
+            .unwrap()
+            .clone();
+        let perm = Permanent::from_card(&chandra, game.perm_ids.get_id(), chandra.owner);
+        let perm_id = perm.id;
+        game.push_event(GameEvent::RegisterPermanent(perm));
+        game.handle_events();
+        let source = AssignedAbility::new(perm_id, chandra.faces[0].perm_abilities[1]);
+        game.add_delayed_trigger(DelayedTrigger::new(source, chandra.owner, perm_id));
+
+        let saved = game.save_to_string().unwrap();
+        let loaded = Game::load_from_string(&saved, try_get_card).unwrap();
+        assert_eq!(loaded.save_to_string().unwrap(), saved);
+        assert_eq!(loaded.abilities.len(), game.abilities.len());
+        assert_eq!(loaded.card_plays.len(), game.card_plays.len());
+        assert!(loaded.battlefield.contains_key(&perm_id));
+        assert_eq!(loaded.delayed_triggers.len(), 1);
+        assert!(loaded.delayed_triggers[0].parts(&loaded).is_some());
+    }
+
+    #[test]
+    fn loading_cards_without_a_definition_is_an_error() {
+        let saved = game().save_to_string().unwrap();
+        let loaded = Game::load_from_string(&saved, |_| None);
+        assert!(matches!(loaded, Err(SaveError::UnknownCard(_))));
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, hash_map::Entry};
+use std::collections::{BTreeMap, btree_map::Entry};
 
 use super::prelude::*;
 
@@ -13,41 +13,62 @@ pub fn check_state_based_actions(facade: &GameFacade) -> Vec<GameEvent> {
     
     let perms : Vec<_> = facade.perms().map(|id|facade.observe_perm(id)).collect();
 
-    // this is far from a complete list of state based actions, but it
-    // covers the big ones.
-    add_toughness_deaths(facade, &perms, &mut ret);
-    add_no_loyalty_deaths(facade, &perms, &mut ret);
-    add_legendary_conflicts(facade, &perms, &mut ret);
-    add_cancel_popo_and_momo_counters(facade, &perms, &mut ret);
+    add_zero_toughness_deaths(&perms, &mut ret);
+    add_damage_deaths(&perms, &mut ret);
+    add_no_loyalty_deaths(&perms, &mut ret);
+    add_legendary_conflicts(&perms, &mut ret);
+    add_world_rule(&perms, &mut ret);
+    add_illegal_attachments(&perms, &mut ret);
+    add_cancel_popo_and_momo_counters(&perms, &mut ret);
     add_sagas_with_no_lore_sacrifice(facade, &perms, &mut ret);
+    add_tokens_outside_battlefield(facade, &mut ret);
     add_player_loses_because_of_health(facade, &mut ret);
+    add_player_loses_because_of_poison(facade, &mut ret);
+    add_player_loses_because_of_empty_library(facade, &mut ret);
+    add_player_loses_because_of_commander_damage(facade, &mut ret);
 
 
     ret
 }
 
-fn add_toughness_deaths(facade: &GameFacade, perms: &Vec<Permanent>, vec: &mut Vec<GameEvent>) {
+fn creature_toughness(perm: &Permanent) -> Option<i32> {
+    if !perm.type_line.is(CardType::Creature) { return None }
+    perm.power_toughness.as_ref().map(|pt| pt.toughness)
+}
+
+/// Creatures with zero or less toughness are put into the graveyard. They
+/// aren't destroyed, so regeneration can't save them.
+fn add_zero_toughness_deaths(perms: &Vec<Permanent>, vec: &mut Vec<GameEvent>) {
     vec.extend(
         perms.iter().filter_map(|perm| {
-            if should_die(facade, perm) {
-                Some(GameEvent::Destroy(perm.id, EventSource::GameRule(GameRule::LethalDamage)))
+            if creature_toughness(perm).is_some_and(|toughness| toughness <= 0) {
+                Some(GameEvent::PutInGraveyard(perm.id, EventSource::GameRule(GameRule::ZeroToughness)))
             } else {
                 None
             }
         }));
 }
 
-fn should_die(facade: &GameFacade, perm: &Permanent) -> bool {
-    perm.type_line.is(CardType::Creature) && 
-    perm.power_toughness
-        .as_ref()
-        .is_some_and(|pt| pt.toughness - perm.damage <= 0 )
+/// Creatures with lethal damage, or any damage from a source with
+/// deathtouch, are destroyed.
+fn add_damage_deaths(perms: &Vec<Permanent>, vec: &mut Vec<GameEvent>) {
+    vec.extend(
+        perms.iter().filter_map(|perm| {
+            let toughness = creature_toughness(perm).filter(|toughness| *toughness > 0)?;
+            if perm.damage >= toughness {
+                Some(GameEvent::Destroy(perm.id, EventSource::GameRule(GameRule::LethalDamage)))
+            } else if perm.deathtouch_damage {
+                Some(GameEvent::Destroy(perm.id, EventSource::GameRule(GameRule::DeathtouchDamage)))
+            } else {
+                None
+            }
+        }));
 }
 
-fn add_no_loyalty_deaths(facade: &GameFacade, perms: &Vec<Permanent>, vec: &mut Vec<GameEvent>) {
+fn add_no_loyalty_deaths(perms: &Vec<Permanent>, vec: &mut Vec<GameEvent>) {
     vec.extend(perms.iter().filter_map(|perm| {
-        if perm.type_line.is(CardType::Planeswalker) && perm.counters.loyalty <= 0 {
-            Some(GameEvent::Destroy(perm.id, EventSource::GameRule(GameRule::NoLoyalty)))
+        if perm.type_line.is(CardType::Planeswalker) && perm.counters.loyalty == 0 {
+            Some(GameEvent::PutInGraveyard(perm.id, EventSource::GameRule(GameRule::NoLoyalty)))
         } else {
             None
         }
@@ -55,8 +76,9 @@ fn add_no_loyalty_deaths(facade: &GameFacade, perms: &Vec<Permanent>, vec: &mut
 }
 
 
-fn add_legendary_conflicts(facade: &GameFacade, perms: &Vec<Permanent>, vec : &mut Vec<GameEvent>) {
-    let mut legend_map : HashMap<(PlayerID, &str), Vec<PermanentID>> = HashMap::new();
+fn add_legendary_conflicts(perms: &Vec<Permanent>, vec : &mut Vec<GameEvent>) {
+    // Ordered, so the same game always checks conflicts in the same order
+    let mut legend_map : BTreeMap<(PlayerID, &str), Vec<PermanentID>> = BTreeMap::new();
 
     for perm in perms.iter() {
         if perm.type_line.is(CardSuperType::Legendary) {
@@ -78,7 +100,35 @@ fn add_legendary_conflicts(facade: &GameFacade, perms: &Vec<Permanent>, vec : &m
     );
 }
 
-fn add_cancel_popo_and_momo_counters(facade: &GameFacade, perms: &Vec<Permanent>, vec : &mut Vec<GameEvent>) {
+/// If there are several world permanents, all but the newest are put 
+/// into their owners' graveyards.
+fn add_world_rule(perms: &Vec<Permanent>, vec : &mut Vec<GameEvent>) {
+    let worlds: Vec<_> = perms.iter().filter(|perm| perm.type_line.is(CardSuperType::World)).collect();
+    let Some(newest) = worlds.iter().map(|perm| perm.timestamp).max() else { return };
+
+    vec.extend(
+        worlds.iter()
+            .filter(|perm| perm.timestamp != newest)
+            .map(|perm| GameEvent::PutInGraveyard(perm.id, GameRule::WorldRule.into()))
+    );
+}
+
+fn add_illegal_attachments(perms: &Vec<Permanent>, vec : &mut Vec<GameEvent>) {
+    for perm in perms.iter() {
+        let host = perm.attached_to.and_then(|host| perms.iter().find(|other| other.id == host));
+        if host.is_some_and(|host| perm.can_attach_to(host)) { continue }
+
+        // Auras that aren't legally attached go to the graveyard, anything
+        // else just becomes unattached.
+        if perm.is_aura() {
+            vec.push(GameEvent::PutInGraveyard(perm.id, GameRule::IllegalAttachment.into()));
+        } else if perm.attached_to.is_some() {
+            vec.push(GameEvent::Unattach(perm.id, GameRule::IllegalAttachment.into()));
+        }
+    }
+}
+
+fn add_cancel_popo_and_momo_counters(perms: &Vec<Permanent>, vec : &mut Vec<GameEvent>) {
     for perm in perms.iter() {
         let popo = perm.counters.plus_one_plus_one;
         let momo = perm.counters.min_one_min_one;
@@ -98,17 +148,26 @@ fn add_cancel_popo_and_momo_counters(facade: &GameFacade, perms: &Vec<Permanent>
 
 fn add_sagas_with_no_lore_sacrifice(facade: &GameFacade, perms: &Vec<Permanent>, vec : &mut Vec<GameEvent>) {
     for perm in perms.iter() {
-        // TODO get sagas to work.
-        //if perm.type_line.is("Saga") && perm.counters.lore == perm.num_chapters {
-        //    vec.push(GameEvent::Sacrifice(perm.id, GameRule::NoLore.into()))
-        //}
+        if !perm.type_line.is(SAGA) { continue }
+
+        let finished = facade.final_chapter(perm.id).is_some_and(|chapter| perm.counters.lore >= chapter);
+        if finished && !facade.has_ability_on_stack(perm.id) {
+            vec.push(GameEvent::Sacrifice(perm.id, GameRule::NoLore.into()))
+        }
     }
 }
 
+/// Tokens that left the battlefield cease to exist.
+fn add_tokens_outside_battlefield(facade: &GameFacade, vec : &mut Vec<GameEvent>) {
+    vec.extend(facade
+        .tokens_elsewhere()
+        .map(|token| GameEvent::CeaseToExist(token, GameRule::TokenOutsideBattlefield.into())));
+}
+
 fn add_player_loses_because_of_health(facade: &GameFacade, vec : &mut Vec<GameEvent>) {
     vec.extend(
         facade.players().into_iter().filter_map( |player|
-            if facade.player_life(player) <= 0 {
+            if facade.player_life(player) <= 0 && facade.can_lose(player) {
                 Some(GameEvent::Lose(player, EventSource::GameRule(GameRule::NoHealth))) 
             } else {
                 None
@@ -116,3 +175,36 @@ fn add_player_loses_because_of_health(facade: &GameFacade, vec : &mut Vec<GameEv
         ));
 }
 
+
+fn add_player_loses_because_of_poison(facade: &GameFacade, vec : &mut Vec<GameEvent>) {
+    vec.extend(
+        facade.players().into_iter().filter_map( |player|
+            if facade.player_poison(player) >= 10 && facade.can_lose(player) {
+                Some(GameEvent::Lose(player, EventSource::GameRule(GameRule::TooMuchPoison))) 
+            } else {
+                None
+            }
+        ));
+}
+
+fn add_player_loses_because_of_empty_library(facade: &GameFacade, vec : &mut Vec<GameEvent>) {
+    vec.extend(
+        facade.players().into_iter().filter_map( |player|
+            if facade.drew_from_empty_library(player) && facade.can_lose(player) {
+                Some(GameEvent::Lose(player, EventSource::GameRule(GameRule::CouldntDraw))) 
+            } else {
+                None
+            }
+        ));
+}
+
+fn add_player_loses_because_of_commander_damage(facade: &GameFacade, vec : &mut Vec<GameEvent>) {
+    vec.extend(
+        facade.players().into_iter().filter_map( |player|
+            if facade.commander_damage(player) >= LETHAL_COMMANDER_DAMAGE && facade.can_lose(player) {
+                Some(GameEvent::Lose(player, EventSource::GameRule(GameRule::CommanderDamage))) 
+            } else {
+                None
+            }
+        ));
+}
@@ -0,0 +1,15 @@
+use serde::{Serialize, Deserialize};
+
+use super::prelude::*;
+
+/// Something that a spell or ability can target.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Target {
+    Permanent(PermanentID),
+    Player(PlayerID),
+    /// A spell or ability on the stack
+    Object(ObjectID),
+}
+
+/// The legal choices for each target of a spell or ability, in order.
+pub type TargetRequirements = Vec<Vec<Target>>;
@@ -1,9 +1,15 @@
+use std::{
+    collections::BTreeSet,
+    sync::{Mutex, OnceLock},
+};
 
+use serde::{Serialize, Deserialize, Deserializer};
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TypeLine {
     pub card_super_types: Vec<CardSuperType>,
     pub card_types: Vec<CardType>,
+    #[serde(deserialize_with = "deserialize_sub_types")]
     pub card_sub_types: Vec<CardSubType>,
 }
 
@@ -18,7 +24,7 @@ impl TypeLine {
 }
 
 
-#[derive(Clone, PartialEq, PartialOrd, Eq)]
+#[derive(Clone, PartialEq, PartialOrd, Eq, Debug, Serialize, Deserialize)]
 pub enum CardType {
     Land,
     Creature,
@@ -29,7 +35,7 @@ pub enum CardType {
     Planeswalker,
 }
 
-#[derive(Clone, PartialEq, PartialOrd, Eq)]
+#[derive(Clone, PartialEq, PartialOrd, Eq, Debug, Serialize, Deserialize)]
 pub enum CardSuperType {
     Basic,
     Legendary,
@@ -41,6 +47,34 @@ pub enum CardSuperType {
 // too many to try and use an enum
 pub type CardSubType = &'static str;
 
+/// Subtypes are static strings, so the ones read from a saved game are
+/// interned. Each different subtype is only ever leaked once, however
+/// many games are loaded.
+fn deserialize_sub_types<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<CardSubType>, D::Error> {
+    static INTERNED: OnceLock<Mutex<BTreeSet<CardSubType>>> = OnceLock::new();
+
+    let sub_types = Vec::<String>::deserialize(deserializer)?;
+    let mut interned = INTERNED
+        .get_or_init(Default::default)
+        .lock()
+        .expect("interned subtypes lock was poisoned");
+    Ok(sub_types
+        .into_iter()
+        .map(|sub_type| match interned.get(sub_type.as_str()) {
+            Some(known) => *known,
+            None => {
+                let leaked: CardSubType = Box::leak(sub_type.into_boxed_str());
+                interned.insert(leaked);
+                leaked
+            }
+        })
+        .collect())
+}
+
+pub const SAGA: CardSubType = "Saga";
+pub const AURA: CardSubType = "Aura";
+pub const EQUIPMENT: CardSubType = "Equipment";
+
 pub trait TypeClassQuery<T> {
     fn add(self, a_new_type: T) -> Self;
     fn is(&self, a_type: T) -> bool;
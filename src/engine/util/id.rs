@@ -1,5 +1,7 @@
 use std::{iter::Iterator, marker::PhantomData, fmt::Debug, any::type_name, hash::Hash, cell::Cell};
 
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+
 pub struct ID<T> (usize, PhantomData<T>);
 
 /// SAFETY: PhantomData holds no data, and it is safe to send usizes between threads
@@ -34,6 +36,18 @@ impl<T> Debug for ID<T> {
 
 impl<T> Copy for ID<T> {}
 
+impl<T> Serialize for ID<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for ID<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        usize::deserialize(deserializer).map(|id| Self(id, PhantomData::default()))
+    }
+}
+
 impl<T> PartialEq for ID<T> {
     fn eq(&self, other: &Self) -> bool {
         self.0.eq(&other.0)
@@ -70,6 +84,25 @@ impl<T> IDFactory<ID<T>> {
     }
 }
 
+impl<T> Clone for IDFactory<ID<T>> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData::default())
+    }
+}
+
+/// Only the last ID given out is saved, the next one continues from it.
+impl<T> Serialize for IDFactory<ID<T>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.get().serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for IDFactory<ID<T>> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        usize::deserialize(deserializer).map(|last| Self(Cell::new(last), PhantomData::default()))
+    }
+}
+
 impl<T> Iterator for IDFactory<ID<T>> {
     type Item = ID<T>;
 
@@ -22,25 +22,32 @@ impl QueryModifier for NullEffect {
 }
 
 impl OneShot for NullEffect {
-    fn activate(&self, _: AbilityID, _: PermanentID, _: &mut Game) {
+    fn activate(&self, _: AbilityID, _: PermanentID, _: &[Target], _: &mut Game) {
     }
 }
 
 #[derive(Clone)]
 pub struct AddManaEffect {
-    mana_type: ManaType
+    mana_type: ManaType,
+    amount: usize,
 }
 impl AddManaEffect {
     pub fn new(mana_type: ManaType) -> Box<Self> {
-        Box::new(Self {mana_type})
+        Self::many(mana_type, 1)
+    }
+
+    pub fn many(mana_type: ManaType, amount: usize) -> Box<Self> {
+        Box::new(Self {mana_type, amount})
     }
 }
 
 impl Effect for AddManaEffect {}
 impl OneShot for AddManaEffect {
-    fn activate(&self, _: AbilityID, perm: PermanentID, game: &mut Game) {
+    fn activate(&self, _: AbilityID, perm: PermanentID, _: &[Target], game: &mut Game) {
         let player_id = game.get(perm).owner;
-        game.push_event(GameEvent::AddMana(player_id, self.mana_type, EventSource::Permanent(perm)))
+        for _ in 0..self.amount {
+            game.push_event(GameEvent::AddMana(player_id, self.mana_type, EventSource::Permanent(perm)))
+        }
     }
 }
 
@@ -74,3 +81,659 @@ impl EventModifier for MiraisMana {
         event.ignored()
     }
 }
+
+/// "You can't lose the game and your opponents can't win the game."
+#[derive(Clone)]
+pub struct CantLoseEffect {}
+impl CantLoseEffect {
+    pub fn new() -> Box<Self> { Box::new(Self{}) }
+}
+
+impl Effect for CantLoseEffect {}
+impl QueryModifier for CantLoseEffect {
+    fn query(&self, _: AbilityID, perm: PermanentID, query: &mut GameQuery, game: &Game) {
+        if let GameQuery::WinLose(ref mut query) = query {
+            if game.get(perm).owner == query.player {
+                query.can_lose = false;
+            } else {
+                query.can_win = false;
+            }
+        }
+    }
+}
+
+/// "Players can't gain life." or "Your opponents can't gain life."
+#[derive(Clone)]
+pub struct CantGainLifeEffect {
+    opponents_only: bool,
+}
+impl CantGainLifeEffect {
+    pub fn players() -> Box<Self> { Box::new(Self { opponents_only: false }) }
+    pub fn opponents() -> Box<Self> { Box::new(Self { opponents_only: true }) }
+}
+
+impl Effect for CantGainLifeEffect {}
+impl QueryModifier for CantGainLifeEffect {
+    fn query(&self, _: AbilityID, perm: PermanentID, query: &mut GameQuery, game: &Game) {
+        let GameQuery::ObservePlayer(ref mut query) = query else { return };
+        if !self.opponents_only || game.get(perm).owner != query.player {
+            query.can_gain_life = false;
+        }
+    }
+}
+
+/// "Creatures can't block."
+#[derive(Clone)]
+pub struct CantBlockEffect {}
+impl CantBlockEffect {
+    pub fn new() -> Box<Self> { Box::new(Self{}) }
+}
+
+impl Effect for CantBlockEffect {}
+impl QueryModifier for CantBlockEffect {
+    fn query(&self, _: AbilityID, _: PermanentID, query: &mut GameQuery, _: &Game) {
+        if let GameQuery::CanBlock(ref mut query) = query {
+            query.can_block = false;
+        }
+    }
+}
+
+/// "Hexproof": it can't be the target of spells or abilities your
+/// opponents control.
+#[derive(Clone)]
+pub struct HexproofEffect {}
+impl HexproofEffect {
+    pub fn new() -> Box<Self> { Box::new(Self{}) }
+}
+
+impl Effect for HexproofEffect {}
+impl QueryModifier for HexproofEffect {
+    fn query(&self, _: AbilityID, perm: PermanentID, query: &mut GameQuery, game: &Game) {
+        let GameQuery::Targetable(ref mut query) = query else { return };
+        if query.target == Target::Permanent(perm) && query.player != game.get(perm).owner {
+            query.targetable = false;
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CreateTokenEffect {
+    name: String,
+    type_line: TypeLine,
+    power_toughness: Option<(i32, i32)>,
+    colors: Vec<ManaType>,
+    keywords: Vec<Keyword>,
+    amount: usize,
+    temporary: bool,
+}
+impl CreateTokenEffect {
+    pub fn new(name: &str, type_line: TypeLine, power_toughness: Option<(i32, i32)>) -> Box<Self> {
+        Box::new(Self { name: name.into(), type_line, power_toughness, colors: vec![], keywords: vec![], amount: 1, temporary: false })
+    }
+
+    pub fn with_colors(mut self: Box<Self>, colors: Vec<ManaType>) -> Box<Self> {
+        self.colors = colors;
+        self
+    }
+
+    pub fn with_keyword(mut self: Box<Self>, keyword: Keyword) -> Box<Self> {
+        self.keywords.push(keyword);
+        self
+    }
+
+    pub fn times(mut self: Box<Self>, amount: usize) -> Box<Self> {
+        self.amount = amount;
+        self
+    }
+
+    /// "They gain haste. Sacrifice them at the beginning of the next end step."
+    pub fn temporary(mut self: Box<Self>) -> Box<Self> {
+        self.temporary = true;
+        self
+    }
+}
+
+impl Effect for CreateTokenEffect {}
+impl OneShot for CreateTokenEffect {
+    fn activate(&self, ability: AbilityID, perm: PermanentID, _: &[Target], game: &mut Game) {
+        let owner = game.get(perm).owner;
+        for _ in 0..self.amount {
+            let mut token = Permanent::token(
+                self.name.clone(), 
+                self.type_line.clone(), 
+                self.power_toughness.map(|(p, t)| PowerToughness::new(p, t)), 
+                game.perm_ids.get_id(), 
+                owner);
+            token.colors = self.colors.clone();
+            token.keywords = self.keywords.clone();
+
+            if self.temporary {
+                token.summoning_sickness = false;
+                game.add_delayed_trigger(DelayedTrigger::new(AssignedAbility::new(perm, ability), owner, token.id));
+            }
+
+            game.push_event(GameEvent::RegisterPermanent(token));
+        }
+    }
+
+    fn delayed_trigger(&self, subject: PermanentID) -> Option<(Box<dyn Trigger>, Box<dyn OneShot>)> {
+        if !self.temporary { return None }
+        Some((StepTrigger::new(TurnStep::End), SacrificeEffect::new(subject)))
+    }
+}
+
+/// "Equip": attach to target creature you control.
+#[derive(Clone)]
+pub struct EquipEffect {}
+impl EquipEffect {
+    pub fn new() -> Box<Self> { Box::new(Self{}) }
+}
+
+impl Effect for EquipEffect {}
+impl OneShot for EquipEffect {
+    fn activate(&self, _: AbilityID, perm: PermanentID, targets: &[Target], game: &mut Game) {
+        if let Some(Target::Permanent(host)) = targets.first() {
+            game.push_event(GameEvent::Attach(perm, *host, EventSource::Permanent(perm)));
+        }
+    }
+
+    fn targets(&self, _: AbilityID, perm: PermanentID, game: &Game) -> TargetRequirements {
+        let controller = game.get(perm).owner;
+        vec![
+            game.battlefield
+                .values()
+                .filter(|creature| creature.owner == controller && creature.id != perm && creature.type_line.is(CardType::Creature))
+                .map(|creature| Target::Permanent(creature.id))
+                .collect()
+        ]
+    }
+}
+
+/// "Enchanted creature gets +X/+Y" and "Equipped creature gets +X/+Y"
+#[derive(Clone)]
+pub struct AttachedBoostEffect {
+    power: i32,
+    toughness: i32,
+}
+impl AttachedBoostEffect {
+    pub fn new(power: i32, toughness: i32) -> Box<Self> { Box::new(Self{power, toughness}) }
+}
+
+impl Effect for AttachedBoostEffect {}
+impl QueryModifier for AttachedBoostEffect {
+    fn query(&self, _: AbilityID, perm: PermanentID, query: &mut GameQuery, game: &Game) {
+        if let GameQuery::ObservePerm(ref mut query) = query {
+            if game.get(perm).attached_to != Some(query.perm.id) { return }
+
+            if let Some(ref mut pt) = query.perm.power_toughness {
+                pt.power += self.power;
+                pt.toughness += self.toughness;
+            }
+        }
+    }
+}
+
+/// "Creatures you control get +X/+Y."
+#[derive(Clone)]
+pub struct AnthemEffect {
+    power: i32,
+    toughness: i32,
+}
+impl AnthemEffect {
+    pub fn new(power: i32, toughness: i32) -> Box<Self> { Box::new(Self{power, toughness}) }
+}
+
+impl Effect for AnthemEffect {}
+impl QueryModifier for AnthemEffect {
+    fn query(&self, _: AbilityID, perm: PermanentID, query: &mut GameQuery, game: &Game) {
+        let GameQuery::ObservePerm(ref mut query) = query else { return };
+        if query.perm.owner != game.get(perm).owner || !query.perm.type_line.is(CardType::Creature) { return }
+
+        if let Some(ref mut pt) = query.perm.power_toughness {
+            pt.power += self.power;
+            pt.toughness += self.toughness;
+        }
+    }
+}
+
+/// "<Subtype>s you control get +X/+Y until end of turn." Only the
+/// permanents there as it resolves are affected.
+#[derive(Clone)]
+pub struct BoostUntilEndOfTurn {
+    sub_type: CardSubType,
+    power: i32,
+    toughness: i32,
+}
+impl BoostUntilEndOfTurn {
+    pub fn yours(sub_type: CardSubType, power: i32, toughness: i32) -> Box<Self> {
+        Box::new(Self{ sub_type, power, toughness })
+    }
+}
+
+impl Effect for BoostUntilEndOfTurn {}
+impl OneShot for BoostUntilEndOfTurn {
+    fn activate(&self, _: AbilityID, perm: PermanentID, _: &[Target], game: &mut Game) {
+        let controller = game.get(perm).owner;
+        let events = game.battlefield
+            .values()
+            .filter(|other| other.owner == controller && other.type_line.is(self.sub_type))
+            .map(|other| GameEvent::BoostUntilEndOfTurn(other.id, self.power, self.toughness, EventSource::Permanent(perm)))
+            .collect();
+        game.push_event(GameEvent::Simultaneously(events));
+    }
+}
+
+/// "Put a <counter> counter on each <color> <card type> you control."
+#[derive(Clone)]
+pub struct AddCountersEachEffect {
+    counter_type: CounterType,
+    color: ManaType,
+    card_type: CardType,
+}
+impl AddCountersEachEffect {
+    pub fn yours(counter_type: CounterType, color: ManaType, card_type: CardType) -> Box<Self> {
+        Box::new(Self{ counter_type, color, card_type })
+    }
+}
+
+impl Effect for AddCountersEachEffect {}
+impl OneShot for AddCountersEachEffect {
+    fn activate(&self, _: AbilityID, perm: PermanentID, _: &[Target], game: &mut Game) {
+        let controller = game.get(perm).owner;
+        let events = game.battlefield
+            .values()
+            .filter(|other| other.owner == controller 
+                && other.colors.contains(&self.color)
+                && other.type_line.is(self.card_type.clone()))
+            .map(|other| GameEvent::AddCounters(
+                GameObjectID::Permanent(other.id), 
+                self.counter_type.clone(), 
+                1, 
+                EventSource::Permanent(perm)))
+            .collect();
+        game.push_event(GameEvent::Simultaneously(events));
+    }
+}
+
+/// "At the beginning of [your] <step>"
+#[derive(Clone)]
+pub struct StepTrigger {
+    step: TurnStep,
+    only_yours: bool,
+    min_life: Option<i32>,
+}
+impl StepTrigger {
+    pub fn new(step: TurnStep) -> Box<Self> {
+        Box::new(Self { step, only_yours: false, min_life: None })
+    }
+
+    pub fn yours(mut self: Box<Self>) -> Box<Self> {
+        self.only_yours = true;
+        self
+    }
+
+    /// "if you have N or more life"
+    pub fn if_life_at_least(mut self: Box<Self>, life: i32) -> Box<Self> {
+        self.min_life = Some(life);
+        self
+    }
+}
+
+impl Effect for StepTrigger {}
+impl Trigger for StepTrigger {
+    fn triggers_on(&self, _: AbilityID, perm: PermanentID, event: &GameEvent, game: &Game) -> bool {
+        match event {
+            GameEvent::Step(step) if *step == self.step => 
+                !self.only_yours || game.is_active(game.get(perm).owner),
+            _ => false,
+        }
+    }
+
+    fn condition(&self, _: AbilityID, perm: PermanentID, game: &Game) -> bool {
+        match self.min_life {
+            Some(life) => game.battlefield.get(&perm)
+                .is_some_and(|perm| game.observe_player(perm.owner).life_total >= life),
+            None => true,
+        }
+    }
+}
+
+/// Sacrifices the given permanent, or this one if none is given.
+#[derive(Clone)]
+pub struct SacrificeEffect {
+    perm: Option<PermanentID>,
+}
+impl SacrificeEffect {
+    pub fn new(perm: PermanentID) -> Box<Self> { Box::new(Self{ perm: Some(perm) }) }
+    pub fn this() -> Box<Self> { Box::new(Self{ perm: None }) }
+}
+
+impl Effect for SacrificeEffect {}
+impl OneShot for SacrificeEffect {
+    fn activate(&self, _: AbilityID, perm: PermanentID, _: &[Target], game: &mut Game) {
+        let victim = self.perm.unwrap_or(perm);
+        // It might have left the battlefield already
+        if game.battlefield.contains_key(&victim) {
+            game.push_event(GameEvent::Sacrifice(victim, EventSource::Permanent(perm)));
+        }
+    }
+}
+
+/// "You win the game."
+#[derive(Clone)]
+pub struct WinGameEffect {}
+impl WinGameEffect {
+    pub fn new() -> Box<Self> { Box::new(Self{}) }
+}
+
+impl Effect for WinGameEffect {}
+impl OneShot for WinGameEffect {
+    fn activate(&self, _: AbilityID, perm: PermanentID, _: &[Target], game: &mut Game) {
+        let player_id = game.get(perm).owner;
+        game.push_event(GameEvent::Win(player_id, EventSource::Permanent(perm)));
+    }
+}
+
+/// "Creature and enchantment spells you control can't be countered."
+#[derive(Clone)]
+pub struct CantBeCounteredEffect {
+    card_types: Vec<CardType>,
+}
+impl CantBeCounteredEffect {
+    pub fn new(card_types: Vec<CardType>) -> Box<Self> { Box::new(Self{ card_types }) }
+}
+
+impl Effect for CantBeCounteredEffect {}
+impl QueryModifier for CantBeCounteredEffect {
+    fn query(&self, _: AbilityID, perm: PermanentID, query: &mut GameQuery, game: &Game) {
+        let GameQuery::Counterable(ref mut query) = query else { return };
+        let controller = game.get(perm).owner;
+
+        let protected = game.game_stack
+            .iter()
+            .find(|object| object.id == query.id && object.controller == controller)
+            .and_then(|object| object.card)
+            .is_some_and(|card| self.card_types
+                .iter()
+                .any(|card_type| game.get(card).attrs().type_line.is(card_type.clone())));
+
+        if protected {
+            query.can_be_countered = false;
+        }
+    }
+}
+
+/// Makes spells cost more or less, like "Instant and sorcery spells you
+/// cast cost {1} less" or "Noncreature spells cost {1} more".
+#[derive(Clone)]
+pub struct CostModifierEffect {
+    card_types: Vec<CardType>,
+    /// Whether the card types are the ones affected, or the ones that aren't
+    excluded: bool,
+    yours_only: bool,
+    reduction: usize,
+    increase: usize,
+}
+impl CostModifierEffect {
+    /// Spells of the given types that you cast cost less.
+    pub fn reduce(card_types: Vec<CardType>, amount: usize) -> Box<Self> {
+        Box::new(Self{ card_types, excluded: false, yours_only: true, reduction: amount, increase: 0 })
+    }
+
+    /// Every spell not of the given types costs more.
+    pub fn tax_except(card_types: Vec<CardType>, amount: usize) -> Box<Self> {
+        Box::new(Self{ card_types, excluded: true, yours_only: false, reduction: 0, increase: amount })
+    }
+}
+
+impl Effect for CostModifierEffect {}
+impl QueryModifier for CostModifierEffect {
+    fn query(&self, _: AbilityID, perm: PermanentID, query: &mut GameQuery, game: &Game) {
+        let GameQuery::Cost(ref mut query) = query else { return };
+        let CostSource::CardPlay(as_card_play) = query.source else { return };
+        if self.yours_only && query.player != game.get(perm).owner { return }
+
+        let type_line = &game.spell_attrs(as_card_play).type_line;
+        let matches = self.card_types.iter().any(|card_type| type_line.is(card_type.clone()));
+        if matches == self.excluded { return }
+
+        query.increase += self.increase;
+        query.reduction += self.reduction;
+    }
+}
+
+/// "You may play lands and cast spells from the top of your library."
+#[derive(Clone)]
+pub struct PlayFromTopOfLibraryEffect {}
+impl PlayFromTopOfLibraryEffect {
+    pub fn new() -> Box<Self> { Box::new(Self{}) }
+}
+
+impl Effect for PlayFromTopOfLibraryEffect {}
+impl QueryModifier for PlayFromTopOfLibraryEffect {
+    fn query(&self, _: AbilityID, perm: PermanentID, query: &mut GameQuery, game: &Game) {
+        let GameQuery::CardPlays(ref mut query) = query else { return };
+        let controller = game.get(perm).owner;
+        if game.cards.top_of_library(controller) != Some(query.id) { return }
+
+        // The card can be played the way it would be from hand
+        for card_play in game.get(query.id).card_plays.iter() {
+            if game.get(*card_play).zones.contains(&PlayZone::Hand) && !query.card_plays.contains(card_play) {
+                query.card_plays.push(*card_play);
+            }
+        }
+    }
+}
+
+/// "Transform this", optionally only if the top card of your library is
+/// one of the given types.
+#[derive(Clone)]
+pub struct TransformEffect {
+    top_card_types: Option<Vec<CardType>>,
+}
+impl TransformEffect {
+    pub fn new() -> Box<Self> { Box::new(Self{ top_card_types: None }) }
+
+    /// "Look at the top card of your library. If it's a <type> card, you 
+    /// may reveal it and transform this."
+    pub fn if_top_card_is(card_types: Vec<CardType>) -> Box<Self> {
+        Box::new(Self{ top_card_types: Some(card_types) })
+    }
+}
+
+impl Effect for TransformEffect {}
+impl OneShot for TransformEffect {
+    fn activate(&self, _: AbilityID, perm: PermanentID, _: &[Target], game: &mut Game) {
+        if let Some(ref card_types) = self.top_card_types {
+            let owner = game.get(perm).owner;
+            let Some(top) = game.cards.top_of_library(owner) else { return };
+            let type_line = &game.get(top).attrs().type_line;
+            if !card_types.iter().any(|card_type| type_line.is(card_type.clone())) { return }
+        }
+
+        game.push_event(GameEvent::Transform(perm, EventSource::Permanent(perm)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::impls::cards::get_card;
+
+    use super::*;
+
+    /// "Creatures can't attack."
+    #[derive(Clone)]
+    struct CantAttackEffect {}
+
+    impl Effect for CantAttackEffect {}
+    impl QueryModifier for CantAttackEffect {
+        fn query(&self, _: AbilityID, _: PermanentID, query: &mut GameQuery, _: &Game) {
+            if let GameQuery::CanAttack(ref mut query) = query {
+                query.can_attack = false;
+            }
+        }
+    }
+
+    fn enchantment(definition: &str, effect: Box<dyn QueryModifier>) -> LatentCard {
+        let mut card = LatentCard::new(
+            definition.into(),
+            ManaCost::empty(),
+            "".into(),
+            TypeLine::empty().add(CardType::Enchantment),
+            vec![LatentAbility { class: AbilityClass::Static(effect), description: "".into() }],
+            vec![],
+            None,
+        );
+        card.definition = definition.into();
+        card
+    }
+
+    /// A game where each player has the given cards in their library.
+    fn game(first: Vec<LatentCard>, second: Vec<LatentCard>) -> (Game, PlayerID, PlayerID) {
+        let game = Game::new(vec![first, second], GameConfig::constructed());
+        let (first, second) = (game.players[0].id, game.players[1].id);
+        (game, first, second)
+    }
+
+    /// Puts the card from the owner's library onto the battlefield.
+    fn enter(game: &mut Game, owner: PlayerID, definition: &str) -> PermanentID {
+        let card = game.get(card_in_library(game, owner, definition)).clone();
+        let perm = Permanent::from_card(&card, game.perm_ids.get_id(), owner);
+        let id = perm.id;
+        game.push_event(GameEvent::RegisterPermanent(perm));
+        game.handle_events();
+        id
+    }
+
+    fn card_in_library(game: &Game, owner: PlayerID, definition: &str) -> CardID {
+        game.cards
+            .get_cards(Zone::Deck(owner))
+            .into_iter()
+            .find(|card| card.definition == definition)
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn opponents_that_cant_gain_life_gain_none() {
+        let (mut game, you, opponent) = game(vec![enchantment("no life gain", CantGainLifeEffect::opponents())], vec![]);
+        let perm = enter(&mut game, you, "no life gain");
+
+        game.push_event(GameEvent::GainLife(you, 3, EventSource::Permanent(perm)));
+        game.push_event(GameEvent::GainLife(opponent, 3, EventSource::Permanent(perm)));
+        game.handle_events();
+        assert_eq!(game.observe_player(you).life_total, 23);
+        assert_eq!(game.observe_player(opponent).life_total, 20);
+        assert!(!game.observe_player(opponent).can_gain_life);
+    }
+
+    #[test]
+    fn observed_players_know_their_hand_size() {
+        let (mut game, you, _) = game(vec![get_card("mountain"), get_card("mountain")], vec![]);
+        game.push_event(GameEvent::DrawCard(you));
+        game.handle_events();
+        assert_eq!(game.observe_player(you).hand_size, 1);
+    }
+
+    #[test]
+    fn creatures_cant_attack() {
+        let (mut game, you, opponent) = game(
+            vec![get_card("goblin assailant"), enchantment("no attacks", Box::new(CantAttackEffect {}))],
+            vec![],
+        );
+        let goblin = enter(&mut game, you, "goblin assailant");
+        game.get_mut(goblin).summoning_sickness = false;
+        let can_attack = |game: &Game| game
+            .query(CanAttackQuery::new(goblin, AttackTarget::Player(opponent)), &AbilityOrdering::build_from(game))
+            .can_attack;
+
+        assert!(can_attack(&game));
+        enter(&mut game, you, "no attacks");
+        assert!(!can_attack(&game));
+    }
+
+    #[test]
+    fn creatures_cant_block_with_bedlam() {
+        let (mut game, you, opponent) = game(
+            vec![get_card("goblin assailant"), get_card("bedlam")],
+            vec![get_card("goblin assailant")],
+        );
+        let attacker = enter(&mut game, you, "goblin assailant");
+        let blocker = enter(&mut game, opponent, "goblin assailant");
+        let can_block = |game: &Game| game
+            .query(CanBlockQuery::new(blocker, attacker), &AbilityOrdering::build_from(game))
+            .can_block;
+
+        assert!(can_block(&game));
+        enter(&mut game, you, "bedlam");
+        assert!(!can_block(&game));
+    }
+
+    #[test]
+    fn hexproof_permanents_cant_be_targeted_by_opponents() {
+        let (mut game, you, opponent) = game(vec![get_card("carnage tyrant")], vec![]);
+        let tyrant = enter(&mut game, you, "carnage tyrant");
+
+        assert!(game.is_targetable(you, Target::Permanent(tyrant)));
+        assert!(!game.is_targetable(opponent, Target::Permanent(tyrant)));
+    }
+
+    #[test]
+    fn platinum_angel_stops_losing_and_winning() {
+        let (mut game, you, opponent) = game(vec![get_card("platinum angel")], vec![]);
+        enter(&mut game, you, "platinum angel");
+
+        assert!(!game.win_lose(you).can_lose);
+        assert!(!game.win_lose(opponent).can_win);
+        assert!(game.win_lose(opponent).can_lose);
+    }
+
+    #[test]
+    fn spells_cost_less_with_goblin_electromancer() {
+        let (mut game, you, _) = game(vec![get_card("goblin electromancer"), get_card("counterspell")], vec![]);
+        let counterspell = card_in_library(&game, you, "counterspell");
+        let as_card_play = AssignedCardPlay::new(counterspell, game.get(counterspell).card_plays[0]);
+        let cost = Cost::empty().with_mana(ManaCost::new(vec![], 2));
+        let generic = |game: &Game| game
+            .total_cost(CostSource::CardPlay(as_card_play), you, 0, cost.clone())
+            .mana_cost
+            .generic_mana;
+
+        assert_eq!(generic(&game), 2);
+        enter(&mut game, you, "goblin electromancer");
+        assert_eq!(generic(&game), 1);
+    }
+
+    #[test]
+    fn destiny_spinner_protects_creature_spells() {
+        let (mut game, you, _) = game(vec![get_card("destiny spinner"), get_card("goblin assailant")], vec![]);
+        let goblin = card_in_library(&game, you, "goblin assailant");
+        let as_card_play = AssignedCardPlay::new(goblin, game.get(goblin).card_plays[0]);
+        let object = game.get(as_card_play.card_play).spawn.spawn(as_card_play, &game);
+        let object_id = object.id;
+        game.game_stack.push(object);
+        let counterable = |game: &Game| game
+            .query(CounterableQuery::new(object_id), &AbilityOrdering::build_from(game))
+            .can_be_countered;
+
+        assert!(counterable(&game));
+        enter(&mut game, you, "destiny spinner");
+        assert!(!counterable(&game));
+    }
+
+    #[test]
+    fn chandra_puts_loyalty_on_red_planeswalkers() {
+        let (mut game, you, _) = game(vec![get_card("chandra acolyte of flame"), get_card("chandra novice pyromancer")], vec![]);
+        let acolyte = enter(&mut game, you, "chandra acolyte of flame");
+        let novice = enter(&mut game, you, "chandra novice pyromancer");
+        let loyalty = |game: &Game, perm: PermanentID| game.get(perm).counters.loyalty;
+        let (acolyte_loyalty, novice_loyalty) = (loyalty(&game, acolyte), loyalty(&game, novice));
+
+        let ability = game.get(acolyte).abilities[0];
+        AddCountersEachEffect::yours(CounterType::Loyalty, ManaType::Red, CardType::Planeswalker)
+            .activate(ability, acolyte, &[], &mut game);
+        game.handle_events();
+        assert_eq!(loyalty(&game, acolyte), acolyte_loyalty + 1);
+        assert_eq!(loyalty(&game, novice), novice_loyalty + 1);
+    }
+}
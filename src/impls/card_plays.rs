@@ -1,5 +1,6 @@
 use crate::engine::prelude::*;
 
+#[derive(Clone)]
 pub struct CastSpell {}
 impl CastSpell {
     pub fn new() -> Box<Self> {
@@ -7,22 +8,375 @@ impl CastSpell {
     }
 }
 
+fn card_cost(as_card_play: AssignedCardPlay, game: &Game) -> Cost {
+    game.spell_attrs(as_card_play).cost.clone()
+        .map(|mana_cost| Cost::empty().with_mana(mana_cost))
+        .unwrap_or_else(Cost::empty)
+}
+
 impl Spawner for CastSpell {
-    fn spawn(&self, card_id: CardID, game: &Game) -> Object {
+    fn spawn(&self, as_card_play: AssignedCardPlay, game: &Game) -> Object {
+        let card = game.get(as_card_play.card);
+        let type_line = &game.spell_attrs(as_card_play).type_line;
+        if type_line.is(CardType::Instant) || type_line.is(CardType::Sorcery) {
+            return Object::from_spell(as_card_play, card.owner, game.object_ids.get_id())
+        }
+
         let new_perm_id = game.perm_ids.get_id();
-        let card = game.get(card_id);
-        let permanent = Permanent::from_card(card, new_perm_id, card.owner);
-        permanent.into()
+        let face = game.get(as_card_play.card_play).face;
+        let permanent = Permanent::from_card_face(card, face, new_perm_id, card.owner);
+        Object::from_perm(permanent, game.object_ids.get_id())
+    }
+
+    fn cost(&self, as_card_play: AssignedCardPlay, game: &Game) -> Cost {
+        card_cost(as_card_play, game)
+    }
+
+    fn targets(&self, as_card_play: AssignedCardPlay, game: &Game) -> TargetRequirements {
+        let attrs = game.spell_attrs(as_card_play);
+        // Aura spells target what they will enchant
+        match attrs.enchant {
+            Some(ref card_type) if attrs.type_line.is(AURA) => vec![
+                game.battlefield
+                    .values()
+                    .filter(|perm| perm.type_line.is(card_type.clone()))
+                    .map(|perm| Target::Permanent(perm.id))
+                    .collect()
+            ],
+            _ => vec![],
+        }
+    }
+}
+
+/// "Counter target spell" or "Counter target activated or triggered ability"
+#[derive(Clone)]
+pub struct CastCounterspell {
+    spells: bool,
+}
+impl CastCounterspell {
+    pub fn spells() -> Box<Self> {
+        Box::new(Self{ spells: true })
+    }
+
+    pub fn abilities() -> Box<Self> {
+        Box::new(Self{ spells: false })
+    }
+}
+
+impl Spawner for CastCounterspell {
+    fn spawn(&self, as_card_play: AssignedCardPlay, game: &Game) -> Object {
+        let owner = game.get(as_card_play.card).owner;
+        Object::from_spell(as_card_play, owner, game.object_ids.get_id())
+    }
+
+    fn cost(&self, as_card_play: AssignedCardPlay, game: &Game) -> Cost {
+        card_cost(as_card_play, game)
+    }
+
+    fn targets(&self, _: AssignedCardPlay, game: &Game) -> TargetRequirements {
+        vec![
+            game.game_stack
+                .iter()
+                .filter(|object| object.is_spell() == self.spells)
+                .map(|object| Target::Object(object.id))
+                .collect()
+        ]
+    }
+
+    fn resolve(&self, spell: &Object, game: &mut Game) {
+        let source = EventSource::Player(spell.controller);
+        for target in spell.targets.iter() {
+            if let Target::Object(object) = target {
+                game.push_event(GameEvent::CounterObject(*object, source.clone()));
+            }
+        }
+    }
+}
+
+/// Casts an instant or sorcery with "Choose one or more —". Each mode
+/// resolves its own effect.
+#[derive(Clone)]
+pub struct CastModalSpell {
+    requirements: ModeRequirements,
+    effects: Vec<Box<dyn SpellEffect>>,
+}
+impl CastModalSpell {
+    pub fn new(requirements: ModeRequirements, effects: Vec<Box<dyn SpellEffect>>) -> Box<Self> {
+        assert_eq!(requirements.descriptions.len(), effects.len(), "Every mode needs an effect");
+        Box::new(Self{ requirements, effects })
+    }
+}
+
+impl Spawner for CastModalSpell {
+    fn spawn(&self, as_card_play: AssignedCardPlay, game: &Game) -> Object {
+        let owner = game.get(as_card_play.card).owner;
+        Object::from_spell(as_card_play, owner, game.object_ids.get_id())
+    }
+
+    fn cost(&self, as_card_play: AssignedCardPlay, game: &Game) -> Cost {
+        card_cost(as_card_play, game)
+    }
+
+    fn modes(&self, _: CardID, _: &Game) -> Option<ModeRequirements> {
+        Some(self.requirements.clone())
+    }
+
+    fn mode_targets(&self, mode: usize, card_id: CardID, game: &Game) -> TargetRequirements {
+        self.effects[mode].targets(card_id, game)
+    }
+
+    fn resolve_mode(&self, mode: &ChosenMode, spell: &Object, game: &mut Game) {
+        self.effects[mode.mode].resolve(spell, &mode.targets, game)
+    }
+}
+
+/// Plays the card the same way as another card play, but for a different
+/// cost. Flashback and escape are alternative costs.
+#[derive(Clone)]
+pub struct AlternativeCost {
+    spawn: Box<dyn Spawner>,
+    cost: Cost,
+}
+impl AlternativeCost {
+    pub fn new(spawn: Box<dyn Spawner>, cost: Cost) -> Box<Self> {
+        Box::new(Self{ spawn, cost })
+    }
+}
+
+impl Spawner for AlternativeCost {
+    fn spawn(&self, as_card_play: AssignedCardPlay, game: &Game) -> Object {
+        self.spawn.spawn(as_card_play, game)
+    }
+
+    fn cost(&self, _: AssignedCardPlay, _: &Game) -> Cost {
+        self.cost.clone()
+    }
+
+    fn targets(&self, as_card_play: AssignedCardPlay, game: &Game) -> TargetRequirements {
+        self.spawn.targets(as_card_play, game)
+    }
+
+    fn resolve(&self, spell: &Object, game: &mut Game) {
+        self.spawn.resolve(spell, game)
+    }
+
+    fn modes(&self, card_id: CardID, game: &Game) -> Option<ModeRequirements> {
+        self.spawn.modes(card_id, game)
     }
 
-    fn cost(&self, card_id: CardID, game: &Game) -> Cost {
-        game.get(card_id).attrs.cost.clone()
-            .map(|mana_cost| Cost::empty().with_mana(mana_cost))
-            .unwrap_or_else(Cost::empty)
+    fn mode_targets(&self, mode: usize, card_id: CardID, game: &Game) -> TargetRequirements {
+        self.spawn.mode_targets(mode, card_id, game)
     }
+
+    fn resolve_mode(&self, mode: &ChosenMode, spell: &Object, game: &mut Game) {
+        self.spawn.resolve_mode(mode, spell, game)
+    }
+}
+
+/// Casts an instant or sorcery that does a single thing when it resolves.
+#[derive(Clone)]
+pub struct CastEffectSpell {
+    effect: Box<dyn SpellEffect>,
 }
+impl CastEffectSpell {
+    pub fn new(effect: Box<dyn SpellEffect>) -> Box<Self> {
+        Box::new(Self{ effect })
+    }
+}
+
+impl Spawner for CastEffectSpell {
+    fn spawn(&self, as_card_play: AssignedCardPlay, game: &Game) -> Object {
+        let owner = game.get(as_card_play.card).owner;
+        Object::from_spell(as_card_play, owner, game.object_ids.get_id())
+    }
 
+    fn cost(&self, as_card_play: AssignedCardPlay, game: &Game) -> Cost {
+        card_cost(as_card_play, game)
+    }
+
+    fn targets(&self, as_card_play: AssignedCardPlay, game: &Game) -> TargetRequirements {
+        self.effect.targets(as_card_play.card, game)
+    }
+
+    fn resolve(&self, spell: &Object, game: &mut Game) {
+        self.effect.resolve(spell, &spell.targets, game)
+    }
+}
+
+/// Deals damage to any target. The amount is either fixed or X.
+#[derive(Clone)]
+pub struct DamageTarget {
+    amount: Option<u32>,
+}
+impl DamageTarget {
+    pub fn new(amount: u32) -> Box<Self> {
+        Box::new(Self{ amount: Some(amount) })
+    }
+
+    pub fn x() -> Box<Self> {
+        Box::new(Self{ amount: None })
+    }
+
+    fn any_target(game: &Game) -> TargetRequirements {
+        let perms = game.battlefield
+            .values()
+            .filter(|perm| perm.type_line.is(CardType::Creature) || perm.type_line.is(CardType::Planeswalker))
+            .map(|perm| Target::Permanent(perm.id));
+        let players = game.remaining_players()
+            .into_iter()
+            .map(Target::Player);
+
+        vec![perms.chain(players).collect()]
+    }
+
+    fn deal(amount: u32, source: EventSource, targets: &[Target], game: &mut Game) {
+        for target in targets {
+            let victim = match target {
+                Target::Permanent(perm) => GameObjectID::Permanent(*perm),
+                Target::Player(player) => GameObjectID::Player(*player),
+                Target::Object(_) => continue,
+            };
+            game.push_event(GameEvent::DealDamage(victim, amount, source.clone()));
+        }
+    }
+}
+
+impl SpellEffect for DamageTarget {
+    fn targets(&self, _: CardID, game: &Game) -> TargetRequirements {
+        Self::any_target(game)
+    }
+
+    fn resolve(&self, spell: &Object, targets: &[Target], game: &mut Game) {
+        let amount = self.amount.unwrap_or(spell.x);
+        Self::deal(amount, EventSource::Player(spell.controller), targets, game);
+    }
+}
+
+// Abilities have no X, so only fixed amounts make sense for them
+impl Effect for DamageTarget {}
+impl OneShot for DamageTarget {
+    fn activate(&self, _: AbilityID, perm: PermanentID, targets: &[Target], game: &mut Game) {
+        Self::deal(self.amount.unwrap_or(0), EventSource::Permanent(perm), targets, game);
+    }
+
+    fn targets(&self, _: AbilityID, _: PermanentID, game: &Game) -> TargetRequirements {
+        Self::any_target(game)
+    }
+}
+
+/// Happens to the caster or a target player, like "Scry 1, then draw a
+/// card" or "Target player mills five cards". The events happen in the
+/// order they are given.
+#[derive(Clone)]
+pub struct PlayerEvents {
+    targeted: bool,
+    events: fn(PlayerID) -> Vec<GameEvent>,
+}
+impl PlayerEvents {
+    pub fn caster(events: fn(PlayerID) -> Vec<GameEvent>) -> Box<Self> {
+        Box::new(Self{ targeted: false, events })
+    }
+
+    pub fn target_player(events: fn(PlayerID) -> Vec<GameEvent>) -> Box<Self> {
+        Box::new(Self{ targeted: true, events })
+    }
+}
+
+impl SpellEffect for PlayerEvents {
+    fn targets(&self, _: CardID, game: &Game) -> TargetRequirements {
+        if !self.targeted { return vec![] }
+        vec![game.remaining_players().into_iter().map(Target::Player).collect()]
+    }
+
+    fn resolve(&self, spell: &Object, targets: &[Target], game: &mut Game) {
+        let player = match targets.first() {
+            Some(Target::Player(player)) => *player,
+            _ if !self.targeted => spell.controller,
+            _ => return,
+        };
+        // Events are processed last in first out
+        game.push_events((self.events)(player).into_iter().rev());
+    }
+}
+
+/// Deals damage to each creature, or to each of the caster's opponents.
+#[derive(Clone)]
+pub struct DamageEach {
+    amount: u32,
+    creatures: bool,
+}
+impl DamageEach {
+    pub fn creatures(amount: u32) -> Box<Self> {
+        Box::new(Self{ amount, creatures: true })
+    }
+
+    pub fn opponents(amount: u32) -> Box<Self> {
+        Box::new(Self{ amount, creatures: false })
+    }
+}
+
+impl SpellEffect for DamageEach {
+    fn resolve(&self, spell: &Object, _: &[Target], game: &mut Game) {
+        let caster = spell.controller;
+        let victims: Vec<_> = if self.creatures {
+            game.battlefield
+                .values()
+                .filter(|perm| perm.type_line.is(CardType::Creature))
+                .map(|perm| GameObjectID::Permanent(perm.id))
+                .collect()
+        } else {
+            game.remaining_players()
+                .into_iter()
+                .filter(|player| *player != caster)
+                .map(GameObjectID::Player)
+                .collect()
+        };
+
+        let events = victims
+            .into_iter()
+            .map(|victim| GameEvent::DealDamage(victim, self.amount, EventSource::Player(caster)))
+            .collect();
+        game.push_event(GameEvent::Simultaneously(events));
+    }
+}
+
+/// "Destroy target artifact", or any other card type.
+#[derive(Clone)]
+pub struct DestroyTarget {
+    card_type: CardType,
+}
+impl DestroyTarget {
+    pub fn new(card_type: CardType) -> Box<Self> {
+        Box::new(Self{ card_type })
+    }
+}
+
+impl SpellEffect for DestroyTarget {
+    fn targets(&self, _: CardID, game: &Game) -> TargetRequirements {
+        vec![
+            game.battlefield
+                .values()
+                .filter(|perm| perm.type_line.is(self.card_type.clone()))
+                .map(|perm| Target::Permanent(perm.id))
+                .collect()
+        ]
+    }
+
+    fn resolve(&self, spell: &Object, targets: &[Target], game: &mut Game) {
+        let source = EventSource::Player(spell.controller);
+        for target in targets {
+            if let Target::Permanent(perm) = target {
+                game.push_event(GameEvent::Destroy(*perm, source.clone()));
+            }
+        }
+    }
+}
+
+/// Gives the card the default way of casting it, unless it already has
+/// its own card plays.
 pub fn def_card_plays(card: &mut LatentCard) {
+    if !card.card_plays.is_empty() { return }
+
     let speed = if card.attributes.type_line.is(CardType::Instant) {
         AbilitySpeed::Instant
     } else {
@@ -3,6 +3,11 @@ use super::card_plays::*;
 use crate::engine::prelude::*;
 
 pub fn get_card(name: &str) -> LatentCard {
+    try_get_card(name).unwrap_or_else(|| panic!("no card named '{}'", name))
+}
+
+/// The card with the given definition name, if there is one.
+pub fn try_get_card(name: &str) -> Option<LatentCard> {
     use CardType::*;
     use CardSuperType::*;
     use ManaType::*;
@@ -29,8 +34,7 @@ pub fn get_card(name: &str) -> LatentCard {
             TypeLine::empty().add(Enchantment),
             vec![
                 LatentAbility {
-                    // TODO implement this static ability
-                    class: AbilityClass::Static(NullEffect::new()),
+                    class: AbilityClass::Static(AnthemEffect::new(1, 1)),
                     description: "Creatures you control get +1/+1.".into(),
                 },
                 LatentAbility {
@@ -52,9 +56,576 @@ pub fn get_card(name: &str) -> LatentCard {
             Some((2, 2))
         ),
 
-        other => panic!("no card named '{}'", other),
+        "platinum angel" => LatentCard::new(
+            "Platinum Angel".into(),
+            ManaCost::new(vec![], 7),
+            "In its heart lies the secret of immortality.".into(),
+            TypeLine::empty().add(Artifact).add(Creature).add("Angel"),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Static(CantLoseEffect::new()),
+                    description: "You can't lose the game and your opponents can't win the game.".into(),
+                },
+            ],
+            vec![],
+            Some((4, 4))
+        ),
+
+        "chandra novice pyromancer" => LatentCard::new(
+            "Chandra, Novice Pyromancer".into(),
+            ManaCost::new(vec![Red], 3),
+            "".into(),
+            TypeLine::empty().add(Legendary).add(Planeswalker).add("Chandra"),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Activated(AbilityCost::empty().with_loyalty(1), BoostUntilEndOfTurn::yours("Elemental", 2, 0)),
+                    description: "+1: Elementals you control get +2/+0 until end of turn.".into(),
+                },
+                LatentAbility {
+                    class: AbilityClass::Activated(AbilityCost::empty().with_loyalty(-1), AddManaEffect::many(Red, 2)),
+                    description: "-1: Add {R}{R}.".into(),
+                },
+                LatentAbility {
+                    class: AbilityClass::Activated(AbilityCost::empty().with_loyalty(-2), DamageTarget::new(2)),
+                    description: "-2: Chandra, Novice Pyromancer deals 2 damage to any target.".into(),
+                },
+            ],
+            vec![],
+            None
+        ).with_loyalty(5),
+
+        "history of benalia" => LatentCard::new(
+            "History of Benalia".into(),
+            ManaCost::new(vec![White, White], 1),
+            "".into(),
+            TypeLine::empty().add(Enchantment).add(SAGA),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Chapter(1, knight_token()),
+                    description: "I - Create a 2/2 white Knight creature token with vigilance.".into(),
+                },
+                LatentAbility {
+                    class: AbilityClass::Chapter(2, knight_token()),
+                    description: "II - Create a 2/2 white Knight creature token with vigilance.".into(),
+                },
+                LatentAbility {
+                    class: AbilityClass::Chapter(3, BoostUntilEndOfTurn::yours("Knight", 2, 1)),
+                    description: "III - Knights you control get +2/+1 until end of turn.".into(),
+                },
+            ],
+            vec![],
+            None
+        ),
+
+        "holy strength" => LatentCard::new(
+            "Holy Strength".into(),
+            ManaCost::new(vec![White], 0),
+            "".into(),
+            TypeLine::empty().add(Enchantment).add(AURA),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Static(AttachedBoostEffect::new(1, 2)),
+                    description: "Enchanted creature gets +1/+2.".into(),
+                },
+            ],
+            vec![],
+            None
+        ).with_enchant(Creature),
+
+        "bonesplitter" => LatentCard::new(
+            "Bonesplitter".into(),
+            ManaCost::new(vec![], 1),
+            "".into(),
+            TypeLine::empty().add(Artifact).add(EQUIPMENT),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Static(AttachedBoostEffect::new(2, 0)),
+                    description: "Equipped creature gets +2/+0.".into(),
+                },
+                LatentAbility {
+                    class: AbilityClass::Activated(
+                        AbilityCost::empty().with_sorcery_speed().with_mana(ManaCost::new(vec![], 1)), 
+                        EquipEffect::new()),
+                    description: "Equip {1}".into(),
+                },
+            ],
+            vec![],
+            None
+        ),
+
+        "felidar sovereign" => LatentCard::new(
+            "Felidar Sovereign".into(),
+            ManaCost::new(vec![White, White], 4),
+            "".into(),
+            TypeLine::empty().add(Creature).add("Cat").add("Beast"),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::StackTriggered(
+                        StepTrigger::new(TurnStep::Upkeep).yours().if_life_at_least(40), 
+                        WinGameEffect::new()),
+                    description: "At the beginning of your upkeep, if you have 40 or more life, you win the game.".into(),
+                },
+            ],
+            vec![],
+            Some((4, 6))
+        ),
+
+        "spark elemental" => LatentCard::new(
+            "Spark Elemental".into(),
+            ManaCost::new(vec![Red], 0),
+            "".into(),
+            TypeLine::empty().add(Creature).add("Elemental"),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::StackTriggered(StepTrigger::new(TurnStep::End), SacrificeEffect::this()),
+                    description: "At the beginning of the end step, sacrifice Spark Elemental.".into(),
+                },
+            ],
+            vec![],
+            Some((3, 1))
+        ),
+
+        "chandra acolyte of flame" => LatentCard::new(
+            "Chandra, Acolyte of Flame".into(),
+            ManaCost::new(vec![Red, Red], 1),
+            "".into(),
+            TypeLine::empty().add(Legendary).add(Planeswalker).add("Chandra"),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Activated(
+                        AbilityCost::empty().with_loyalty(0), 
+                        AddCountersEachEffect::yours(CounterType::Loyalty, Red, Planeswalker)),
+                    description: "0: Put a loyalty counter on each red planeswalker you control.".into(),
+                },
+                LatentAbility {
+                    class: AbilityClass::Activated(
+                        AbilityCost::empty().with_loyalty(0), 
+                        CreateTokenEffect::new("Elemental", elemental_token(), Some((1, 1))).with_colors(vec![Red]).times(2).temporary()),
+                    description: "0: Create two 1/1 red Elemental creature tokens. They gain haste. Sacrifice them at the beginning of the next end step.".into(),
+                },
+            ],
+            vec![],
+            None
+        ).with_loyalty(4),
+
+        "typhoid rats" => LatentCard::new(
+            "Typhoid Rats".into(),
+            ManaCost::new(vec![Black], 0),
+            "Kidnappers caught in Havengul are given two choices: languish in prison or become rat catchers. The smart ones go to prison.".into(),
+            TypeLine::empty().add(Creature).add("Rat"),
+            vec![],
+            vec![],
+            Some((1, 1))
+        ).with_keyword(Keyword::Deathtouch),
+
+        "counterspell" => LatentCard::new(
+            "Counterspell".into(),
+            ManaCost::new(vec![Blue, Blue], 0),
+            "".into(),
+            TypeLine::empty().add(Instant),
+            vec![],
+            vec![
+                CardPlay::new(CastCounterspell::spells(), "Counterspell: Counter target spell.".into(), AbilitySpeed::Instant),
+            ],
+            None
+        ),
+
+        "fiery confluence" => LatentCard::new(
+            "Fiery Confluence".into(),
+            ManaCost::new(vec![Red, Red], 2),
+            "".into(),
+            TypeLine::empty().add(Sorcery),
+            vec![],
+            vec![
+                CardPlay::new(
+                    CastModalSpell::new(
+                        ModeRequirements::choose(3, 3, vec![
+                            "Fiery Confluence deals 1 damage to each creature.".into(),
+                            "Fiery Confluence deals 2 damage to each opponent.".into(),
+                            "Destroy target artifact.".into(),
+                        ]).repeatable(),
+                        vec![
+                            DamageEach::creatures(1),
+                            DamageEach::opponents(2),
+                            DestroyTarget::new(Artifact),
+                        ]),
+                    "Fiery Confluence: Choose three. You may choose the same mode more than once.".into(), 
+                    AbilitySpeed::Sorcery),
+            ],
+            None
+        ),
+
+        "blaze" => LatentCard::new(
+            "Blaze".into(),
+            ManaCost::new(vec![Red], 0).with_x(1),
+            "".into(),
+            TypeLine::empty().add(Sorcery),
+            vec![],
+            vec![
+                CardPlay::new(CastEffectSpell::new(DamageTarget::x()), "Blaze: Deals X damage to any target.".into(), AbilitySpeed::Sorcery),
+            ],
+            None
+        ),
+
+        "goblin electromancer" => LatentCard::new(
+            "Goblin Electromancer".into(),
+            ManaCost::new(vec![Blue, Red], 0),
+            "".into(),
+            TypeLine::empty().add(Creature).add("Goblin").add("Wizard"),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Static(CostModifierEffect::reduce(vec![Instant, Sorcery], 1)),
+                    description: "Instant and sorcery spells you cast cost {1} less to cast.".into(),
+                },
+            ],
+            vec![],
+            Some((2, 2))
+        ),
+
+        "thalia, guardian of thraben" => LatentCard::new(
+            "Thalia, Guardian of Thraben".into(),
+            ManaCost::new(vec![White], 1),
+            "".into(),
+            TypeLine::empty().add(Legendary).add(Creature).add("Human").add("Soldier"),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Static(CostModifierEffect::tax_except(vec![Creature], 1)),
+                    description: "Noncreature spells cost {1} more to cast.".into(),
+                },
+            ],
+            vec![],
+            Some((2, 1))
+        ).with_keyword(Keyword::FirstStrike),
+
+        "frogmite" => LatentCard::new(
+            "Frogmite".into(),
+            ManaCost::new(vec![], 4),
+            "".into(),
+            TypeLine::empty().add(Artifact).add(Creature).add("Frog"),
+            vec![],
+            vec![],
+            Some((2, 2))
+        ).with_affinity(Artifact),
+
+        "firebolt" => LatentCard::new(
+            "Firebolt".into(),
+            ManaCost::new(vec![Red], 0),
+            "".into(),
+            TypeLine::empty().add(Sorcery),
+            vec![],
+            vec![
+                CardPlay::new(CastEffectSpell::new(DamageTarget::new(2)), "Firebolt: Deals 2 damage to any target.".into(), AbilitySpeed::Sorcery),
+                CardPlay::new(
+                    AlternativeCost::new(
+                        CastEffectSpell::new(DamageTarget::new(2)),
+                        ManaCost::new(vec![Red], 4).into()),
+                    "Firebolt, Flashback {4}{R}".into(), 
+                    AbilitySpeed::Sorcery
+                ).from_zones(vec![PlayZone::Graveyard]).leaves_stack_to(PlayZone::Exile),
+            ],
+            None
+        ),
+
+        "sentinel's eyes" => LatentCard::new(
+            "Sentinel's Eyes".into(),
+            ManaCost::new(vec![White], 0),
+            "".into(),
+            TypeLine::empty().add(Enchantment).add(AURA),
+            vec![
+                LatentAbility {
+                    // TODO grant vigilance as well
+                    class: AbilityClass::Static(AttachedBoostEffect::new(1, 1)),
+                    description: "Enchanted creature gets +1/+1 and has vigilance.".into(),
+                },
+            ],
+            vec![
+                CardPlay::new(CastSpell::new(), "Sentinel's Eyes".into(), AbilitySpeed::Sorcery),
+                CardPlay::new(
+                    AlternativeCost::new(
+                        CastSpell::new(), 
+                        Cost::empty().with_mana(ManaCost::new(vec![White], 0)).with_exile_from_graveyard(2)),
+                    "Sentinel's Eyes, Escape {W}, Exile two other cards from your graveyard".into(),
+                    AbilitySpeed::Sorcery
+                ).from_zones(vec![PlayZone::Graveyard]),
+            ],
+            None
+        ).with_enchant(Creature),
+
+        "misthollow griffin" => LatentCard::new(
+            "Misthollow Griffin".into(),
+            ManaCost::new(vec![Blue, Blue], 2),
+            "".into(),
+            TypeLine::empty().add(Creature).add("Griffin"),
+            vec![],
+            vec![
+                CardPlay::new(CastSpell::new(), "Misthollow Griffin".into(), AbilitySpeed::Sorcery)
+                    .from_zones(vec![PlayZone::Hand, PlayZone::Exile]),
+            ],
+            Some((3, 3))
+        ).with_keyword(Keyword::Flying),
+
+        "future sight" => LatentCard::new(
+            "Future Sight".into(),
+            ManaCost::new(vec![Blue, Blue, Blue], 2),
+            "".into(),
+            TypeLine::empty().add(Enchantment),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Static(PlayFromTopOfLibraryEffect::new()),
+                    description: "Play with the top card of your library revealed. You may play lands and cast spells from the top of your library.".into(),
+                },
+            ],
+            vec![],
+            None
+        ),
+
+        "delver of secrets" => LatentCard::new(
+            "Delver of Secrets".into(),
+            ManaCost::new(vec![Blue], 0),
+            "".into(),
+            TypeLine::empty().add(Creature).add("Human").add("Wizard"),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::StackTriggered(
+                        StepTrigger::new(TurnStep::Upkeep).yours(),
+                        TransformEffect::if_top_card_is(vec![Instant, Sorcery])),
+                    description: "At the beginning of your upkeep, look at the top card of your library. You may reveal that card. If an instant or sorcery card is revealed this way, transform Delver of Secrets.".into(),
+                },
+            ],
+            vec![],
+            Some((1, 1))
+        ).with_face(CardLayout::Transform, LatentFace::new(
+            "Insectile Aberration".into(),
+            None,
+            TypeLine::empty().add(Creature).add("Human").add("Insect"),
+            vec![],
+            Some((3, 2))
+        ).with_keyword(Keyword::Flying)),
+
+        "wear // tear" => LatentCard::new(
+            "Wear".into(),
+            ManaCost::new(vec![Red], 1),
+            "".into(),
+            TypeLine::empty().add(Instant),
+            vec![],
+            vec![
+                CardPlay::new(CastEffectSpell::new(DestroyTarget::new(Artifact)), "Wear: Destroy target artifact.".into(), AbilitySpeed::Instant),
+                CardPlay::new(CastEffectSpell::new(DestroyTarget::new(Enchantment)), "Tear: Destroy target enchantment.".into(), AbilitySpeed::Instant)
+                    .with_face(1),
+            ],
+            None
+        ).with_face(CardLayout::Split, LatentFace::new(
+            "Tear".into(),
+            Some(ManaCost::new(vec![White], 0)),
+            TypeLine::empty().add(Instant),
+            vec![],
+            None
+        )),
+
+        "bonecrusher giant" => LatentCard::new(
+            "Bonecrusher Giant".into(),
+            ManaCost::new(vec![Red], 2),
+            "".into(),
+            TypeLine::empty().add(Creature).add("Giant"),
+            // TODO "Whenever Bonecrusher Giant becomes the target of a spell, 
+            // Bonecrusher Giant deals 2 damage to that spell's controller."
+            vec![],
+            vec![
+                // The creature can be cast from exile after going on an adventure
+                CardPlay::new(CastSpell::new(), "Bonecrusher Giant".into(), AbilitySpeed::Sorcery)
+                    .from_zones(vec![PlayZone::Hand, PlayZone::Adventure]),
+                CardPlay::new(CastEffectSpell::new(DamageTarget::new(2)), "Stomp: Damage can't be prevented this turn. Stomp deals 2 damage to any target.".into(), AbilitySpeed::Instant)
+                    .with_face(1)
+                    .leaves_stack_to(PlayZone::Adventure),
+            ],
+            Some((4, 3))
+        ).with_face(CardLayout::Adventure, LatentFace::new(
+            "Stomp".into(),
+            Some(ManaCost::new(vec![Red], 1)),
+            TypeLine::empty().add(Instant).add("Adventure"),
+            vec![],
+            None
+        )),
+
+        "spikefield hazard" => LatentCard::new(
+            "Spikefield Hazard".into(),
+            ManaCost::new(vec![Red], 0),
+            "".into(),
+            TypeLine::empty().add(Instant),
+            vec![],
+            vec![
+                CardPlay::new(CastEffectSpell::new(DamageTarget::new(1)), "Spikefield Hazard: Deals 1 damage to any target.".into(), AbilitySpeed::Instant),
+                CardPlay::new(CastSpell::new(), "Spikefield Cave".into(), AbilitySpeed::Sorcery)
+                    .with_face(1),
+            ],
+            None
+        ).with_face(CardLayout::ModalDoubleFaced, LatentFace::new(
+            "Spikefield Cave".into(),
+            None,
+            TypeLine::empty().add(Land),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Activated(AbilityCost::empty().with_tap(), AddManaEffect::new(Red)),
+                    description: "{T}: Add {R}.".into(),
+                },
+            ],
+            None
+        ).enters_tapped()),
+
+        "opt" => LatentCard::new(
+            "Opt".into(),
+            ManaCost::new(vec![Blue], 0),
+            "".into(),
+            TypeLine::empty().add(Instant),
+            vec![],
+            vec![
+                CardPlay::new(
+                    CastEffectSpell::new(PlayerEvents::caster(|player| vec![GameEvent::Scry(player, 1), GameEvent::DrawCard(player)])), 
+                    "Opt: Scry 1. Draw a card.".into(), 
+                    AbilitySpeed::Instant),
+            ],
+            None
+        ),
+
+        "consider" => LatentCard::new(
+            "Consider".into(),
+            ManaCost::new(vec![Blue], 0),
+            "".into(),
+            TypeLine::empty().add(Instant),
+            vec![],
+            vec![
+                CardPlay::new(
+                    CastEffectSpell::new(PlayerEvents::caster(|player| vec![GameEvent::Surveil(player, 1), GameEvent::DrawCard(player)])), 
+                    "Consider: Surveil 1. Draw a card.".into(), 
+                    AbilitySpeed::Instant),
+            ],
+            None
+        ),
+
+        "tome scour" => LatentCard::new(
+            "Tome Scour".into(),
+            ManaCost::new(vec![Blue], 0),
+            "".into(),
+            TypeLine::empty().add(Sorcery),
+            vec![],
+            vec![
+                CardPlay::new(
+                    CastEffectSpell::new(PlayerEvents::target_player(|player| vec![GameEvent::Mill(player, 5)])), 
+                    "Tome Scour: Target player mills five cards.".into(), 
+                    AbilitySpeed::Sorcery),
+            ],
+            None
+        ),
+
+        "rampant growth" => LatentCard::new(
+            "Rampant Growth".into(),
+            ManaCost::new(vec![Green], 1),
+            "".into(),
+            TypeLine::empty().add(Sorcery),
+            vec![],
+            vec![
+                CardPlay::new(
+                    CastEffectSpell::new(PlayerEvents::caster(|player| vec![GameEvent::SearchLibrary(LibrarySearch::new(
+                        player, 
+                        CardFilter::any().with_super_type(Basic).with_type(Land), 
+                        SearchDestination::BattlefieldTapped))])), 
+                    "Rampant Growth: Search your library for a basic land card, put that card onto the battlefield tapped, then shuffle.".into(), 
+                    AbilitySpeed::Sorcery),
+            ],
+            None
+        ),
+
+        "worldly tutor" => LatentCard::new(
+            "Worldly Tutor".into(),
+            ManaCost::new(vec![Green], 0),
+            "".into(),
+            TypeLine::empty().add(Instant),
+            vec![],
+            vec![
+                CardPlay::new(
+                    CastEffectSpell::new(PlayerEvents::caster(|player| vec![GameEvent::SearchLibrary(LibrarySearch::new(
+                        player, 
+                        CardFilter::any().with_type(Creature), 
+                        SearchDestination::LibraryTop).revealed())])), 
+                    "Worldly Tutor: Search your library for a creature card, reveal it, then shuffle and put the card on top.".into(), 
+                    AbilitySpeed::Instant),
+            ],
+            None
+        ),
+
+        "stifle" => LatentCard::new(
+            "Stifle".into(),
+            ManaCost::new(vec![Blue], 0),
+            "".into(),
+            TypeLine::empty().add(Instant),
+            vec![],
+            vec![
+                CardPlay::new(CastCounterspell::abilities(), "Stifle: Counter target activated or triggered ability.".into(), AbilitySpeed::Instant),
+            ],
+            None
+        ),
+
+        "carnage tyrant" => LatentCard::new(
+            "Carnage Tyrant".into(),
+            ManaCost::new(vec![Green, Green], 4),
+            "".into(),
+            TypeLine::empty().add(Creature).add("Dinosaur"),
+            vec![
+                // TODO trample
+                LatentAbility {
+                    class: AbilityClass::Static(HexproofEffect::new()),
+                    description: "Hexproof".into(),
+                },
+            ],
+            vec![],
+            Some((7, 6))
+        ).cant_be_countered(),
+
+        "destiny spinner" => LatentCard::new(
+            "Destiny Spinner".into(),
+            ManaCost::new(vec![Green], 1),
+            "".into(),
+            TypeLine::empty().add(Enchantment).add(Creature).add("Human"),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Static(CantBeCounteredEffect::new(vec![Creature, Enchantment])),
+                    description: "Creature and enchantment spells you control can't be countered.".into(),
+                },
+            ],
+            vec![],
+            Some((2, 3))
+        ),
+
+        "bedlam" => LatentCard::new(
+            "Bedlam".into(),
+            ManaCost::new(vec![Red, Red], 2),
+            "".into(),
+            TypeLine::empty().add(Enchantment),
+            vec![
+                LatentAbility {
+                    class: AbilityClass::Static(CantBlockEffect::new()),
+                    description: "Creatures can't block.".into(),
+                },
+            ],
+            vec![],
+            None
+        ),
+
+        _ => return None,
     };
 
     def_card_plays(&mut ret);
-    ret
+    ret.definition = name.to_string();
+    Some(ret)
+}
+
+/// A 2/2 white Knight creature token with vigilance.
+fn knight_token() -> Box<CreateTokenEffect> {
+    CreateTokenEffect::new("Knight", TypeLine::empty().add(CardType::Creature).add("Knight"), Some((2, 2)))
+        .with_colors(vec![ManaType::White])
+        .with_keyword(Keyword::Vigilance)
+}
+
+fn elemental_token() -> TypeLine {
+    TypeLine::empty().add(CardType::Creature).add("Elemental")
 }
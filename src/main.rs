@@ -2,10 +2,14 @@ mod engine;
 mod impls;
 mod client;
 
-use impls::cards::get_card;
-use engine::game::Game;
+use impls::cards::{get_card, try_get_card};
+use engine::{game::Game, game_config::GameConfig, replay::{Recorder, Replay}, card::LatentCard};
+use client::Clients;
 use log::{info, LevelFilter};
 
+const REPLAY_FILE: &str = "replay.txt";
+const SAVE_FILE: &str = "save.ron";
+
 
 fn main() {
     // initialize logging
@@ -13,16 +17,42 @@ fn main() {
 
     info!("Entering autoarcana serverside");
 
-    let cards = || vec![
-        get_card("mountain"),
-        get_card("mountain"),
-        get_card("mountain"),
-        get_card("mountain"),
-        get_card("miraris wake"),
-        get_card("miraris wake"),
-        get_card("goblin assailant"),
-        get_card("goblin assailant"),
-    ];
-    let mut game = Game::new(vec![cards(), cards()]);
-    game.run();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (mut game, loaded) = match &args[..] {
+        [flag, path] if flag == "--replay" => {
+            info!("Replaying {}", path);
+            let replay = Replay::load(path).expect("unable to load replay");
+            (replay.game(replay.decks.iter().map(|deck| build_deck(deck)).collect()), false)
+        }
+        [flag, path] if flag == "--load" => {
+            info!("Resuming {}", path);
+            let game = Game::load(path, try_get_card).expect("unable to load saved game");
+            let clients = Clients::launch(&game.remaining_players()).expect("unable to launch clients");
+            (game.with_interface(Box::new(clients)).with_autosave(SAVE_FILE.into()), true)
+        }
+        _ => {
+            let cards = || [
+                "mountain",
+                "mountain",
+                "mountain",
+                "mountain",
+                "miraris wake",
+                "miraris wake",
+                "goblin assailant",
+                "goblin assailant",
+            ].map(String::from).to_vec();
+            let decks = vec![cards(), cards()];
+            let game = Game::new(decks.iter().map(|deck| build_deck(deck)).collect(), GameConfig::constructed());
+            let players: Vec<_> = game.players.iter().map(|player| player.id).collect();
+            let clients = Clients::launch(&players).expect("unable to launch clients");
+            let recorder = Recorder::create(REPLAY_FILE, &game, &decks, Box::new(clients)).expect("unable to create replay file");
+            (game.with_interface(Box::new(recorder)).with_autosave(SAVE_FILE.into()), false)
+        }
+    };
+    let result = if loaded { game.resume() } else { game.run() };
+    info!("Game finished: {:?}", result);
+}
+
+fn build_deck(names: &[String]) -> Vec<LatentCard> {
+    names.iter().map(|name| get_card(name)).collect()
 }